@@ -0,0 +1,619 @@
+//! Macros `#[derive(Parser)]`, `#[derive(Subcommand)]` e `#[derive(ValueEnum)]` do
+//! cliparser. Use pelo próprio cliparser com a feature `derive`
+//!
+//! O código gerado implementa os traits de `cliparser::derive` e se refere ao crate
+//! sempre como `::cliparser`
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{
+    Attribute, Data, DeriveInput, Error, Expr, ExprLit, Fields, FieldsNamed, GenericArgument,
+    Ident, Lit, LitChar, LitStr, PathArguments, Result, Type, parse_macro_input,
+};
+
+/// Gera `Args` e `Parser` para uma struct: campos viram flags, argumentos
+/// posicionais (`#[arg(positional)]`) ou subcomandos (`#[command(subcommand)]`)
+#[proc_macro_derive(Parser, attributes(command, arg))]
+pub fn derive_parser(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_parser(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// Gera `Subcommand` para um enum: cada variante vira um subcomando
+#[proc_macro_derive(Subcommand, attributes(command, arg))]
+pub fn derive_subcommand(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_subcommand(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// Gera `ValueEnum` e `FlagArg` para um enum de variantes sem campos, que viram os
+/// valores possíveis da flag
+#[proc_macro_derive(ValueEnum, attributes(value))]
+pub fn derive_value_enum(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_value_enum(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// Opções de `#[command(...)]` em structs e variantes
+#[derive(Default)]
+struct CommandAttrs {
+    name: Option<String>,
+    version: Option<String>,
+    about: Option<String>,
+    subcommand: bool,
+}
+
+impl CommandAttrs {
+    fn parse(attrs: &[Attribute]) -> Result<Self> {
+        let mut parsed = Self::default();
+
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("command")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("name") {
+                    parsed.name = Some(meta.value()?.parse::<LitStr>()?.value());
+                } else if meta.path.is_ident("version") {
+                    parsed.version = Some(meta.value()?.parse::<LitStr>()?.value());
+                } else if meta.path.is_ident("about") {
+                    parsed.about = Some(meta.value()?.parse::<LitStr>()?.value());
+                } else if meta.path.is_ident("subcommand") {
+                    parsed.subcommand = true;
+                } else {
+                    return Err(meta.error("opção desconhecida em #[command]"));
+                }
+                Ok(())
+            })?;
+        }
+
+        Ok(parsed)
+    }
+}
+
+/// Opções de `#[arg(...)]` em campos
+#[derive(Default)]
+struct ArgAttrs {
+    long: Option<String>,
+    /// `Some(None)` para `short` sem valor, que usa a primeira letra do nome
+    short: Option<Option<char>>,
+    env: Option<String>,
+    default_value: Option<LitStr>,
+    global: bool,
+    positional: bool,
+    allow_hyphen_values: bool,
+}
+
+impl ArgAttrs {
+    fn parse(attrs: &[Attribute]) -> Result<Self> {
+        let mut parsed = Self::default();
+
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("arg")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("long") {
+                    parsed.long = Some(meta.value()?.parse::<LitStr>()?.value());
+                } else if meta.path.is_ident("short") {
+                    if meta.input.peek(syn::Token![=]) {
+                        parsed.short = Some(Some(meta.value()?.parse::<LitChar>()?.value()));
+                    } else {
+                        parsed.short = Some(None);
+                    }
+                } else if meta.path.is_ident("env") {
+                    parsed.env = Some(meta.value()?.parse::<LitStr>()?.value());
+                } else if meta.path.is_ident("default_value") {
+                    parsed.default_value = Some(meta.value()?.parse::<LitStr>()?);
+                } else if meta.path.is_ident("global") {
+                    parsed.global = true;
+                } else if meta.path.is_ident("positional") {
+                    parsed.positional = true;
+                } else if meta.path.is_ident("allow_hyphen_values") {
+                    parsed.allow_hyphen_values = true;
+                } else {
+                    return Err(meta.error("opção desconhecida em #[arg]"));
+                }
+                Ok(())
+            })?;
+        }
+
+        Ok(parsed)
+    }
+}
+
+/// Primeiro parágrafo dos doc comments, em uma linha
+fn doc_comment(attrs: &[Attribute]) -> String {
+    let mut lines = Vec::new();
+
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("doc")) {
+        if let syn::Meta::NameValue(name_value) = &attr.meta
+            && let Expr::Lit(ExprLit {
+                lit: Lit::Str(text),
+                ..
+            }) = &name_value.value
+        {
+            lines.push(text.value().trim().to_string());
+        }
+    }
+
+    lines
+        .into_iter()
+        .skip_while(String::is_empty)
+        .take_while(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// `dry_run` e `DryRun` viram `dry-run`. O hífen só entra entre uma minúscula e
+/// uma maiúscula, então siglas ficam juntas: `HTTPServer` vira `httpserver`
+fn kebab_case(ident: &Ident) -> String {
+    let ident = ident.to_string();
+    let ident = ident.strip_prefix("r#").unwrap_or(&ident);
+    let mut name = String::new();
+    let mut previous_lowercase = false;
+
+    for ch in ident.chars() {
+        if ch == '_' {
+            name.push('-');
+        } else if ch.is_uppercase() {
+            if previous_lowercase {
+                name.push('-');
+            }
+            name.extend(ch.to_lowercase());
+        } else {
+            name.push(ch);
+        }
+        previous_lowercase = ch.is_lowercase();
+    }
+
+    name
+}
+
+/// Nome do tipo de um campo sem o caminho, com o argumento de `Vec<T>`: `i64`,
+/// `Vec<String>`
+fn type_name(ty: &Type) -> Option<String> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+    if segment.ident != "Vec" {
+        return Some(segment.ident.to_string());
+    }
+    let PathArguments::AngleBracketed(arguments) = &segment.arguments else {
+        return None;
+    };
+    match arguments.args.first()? {
+        GenericArgument::Type(inner) => Some(format!("Vec<{}>", type_name(inner)?)),
+        _ => None,
+    }
+}
+
+/// Verifica em tempo de compilação o `default_value` de campos com os tipos de
+/// `FlagArg` do cliparser. Outros tipos são verificados por `App::validate`
+fn check_default_value(ty: &Type, default: &LitStr, name: &str) -> Result<()> {
+    let value = default.value();
+    let valid_integer = |value: &str| value.trim().parse::<i64>().is_ok();
+
+    let expected = match type_name(ty).as_deref() {
+        Some("bool") => {
+            let valid = ["true", "false", "yes", "no", "1", "0"]
+                .contains(&value.to_ascii_lowercase().as_str());
+            (!valid).then_some("true, false, yes, no, 1 ou 0")
+        }
+        Some("i64") => (value.parse::<i64>().is_err()).then_some("um inteiro"),
+        Some("f64") => (value.parse::<f64>().is_err()).then_some("um número decimal"),
+        Some("Vec<i64>") => {
+            (!value.split(',').all(valid_integer)).then_some("inteiros separados por vírgula")
+        }
+        _ => None,
+    };
+
+    match expected {
+        Some(expected) => Err(Error::new_spanned(
+            default,
+            format!("valor padrão inválido para --{}: esperado {}", name, expected),
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Tipo `T` de um campo `Option<T>`
+fn option_inner(ty: &Type) -> Option<&Type> {
+    let Type::Path(path) = ty else {
+        return None;
+    };
+    let segment = path.path.segments.last()?;
+    if segment.ident != "Option" {
+        return None;
+    }
+    let PathArguments::AngleBracketed(arguments) = &segment.arguments else {
+        return None;
+    };
+    match arguments.args.first()? {
+        GenericArgument::Type(inner) => Some(inner),
+        _ => None,
+    }
+}
+
+/// Código que adiciona os campos a `command` e inicializadores que os leem de `parsed`
+struct FieldsCode {
+    augment: Vec<TokenStream2>,
+    init: Vec<TokenStream2>,
+}
+
+fn expand_fields(fields: &FieldsNamed) -> Result<FieldsCode> {
+    let mut code = FieldsCode {
+        augment: Vec::new(),
+        init: Vec::new(),
+    };
+    let mut positional_index = 0usize;
+    let mut has_subcommand = false;
+
+    for field in &fields.named {
+        let ident = field.ident.as_ref().expect("campo nomeado");
+        let doc = doc_comment(&field.attrs);
+        let optional = option_inner(&field.ty);
+        let ty = optional.unwrap_or(&field.ty);
+
+        if CommandAttrs::parse(&field.attrs)?.subcommand {
+            if has_subcommand {
+                return Err(Error::new_spanned(
+                    field,
+                    "apenas um campo pode ser #[command(subcommand)]",
+                ));
+            }
+            has_subcommand = true;
+
+            code.augment.push(quote! {
+                command = <#ty as ::cliparser::Subcommand>::augment_subcommands(command);
+            });
+
+            let value = quote! { <#ty as ::cliparser::Subcommand>::from_parsed(parsed)? };
+            code.init.push(if optional.is_some() {
+                quote! { #ident: #value }
+            } else {
+                quote! {
+                    #ident: #value.ok_or_else(|| ::cliparser::AppError::ParseError {
+                        message: "Nenhum subcomando informado".to_string(),
+                    })?
+                }
+            });
+            continue;
+        }
+
+        let attrs = ArgAttrs::parse(&field.attrs)?;
+        let name = attrs.long.clone().unwrap_or_else(|| kebab_case(ident));
+
+        if attrs.positional {
+            let index = positional_index;
+            positional_index += 1;
+            let required = optional.is_none();
+            let hyphen = attrs.allow_hyphen_values;
+
+            code.augment.push(quote! {
+                command = command.add_positional_arg(
+                    ::cliparser::command::PositionalArg::new(#name)
+                        .description(#doc)
+                        .required(#required)
+                        .allow_hyphen_values(#hyphen),
+                );
+            });
+
+            let value =
+                quote! { ::cliparser::derive::positional_value::<#ty>(parsed, #index, #name)? };
+            code.init.push(if optional.is_some() {
+                quote! { #ident: #value }
+            } else {
+                quote! {
+                    #ident: #value.ok_or_else(|| ::cliparser::AppError::ParseError {
+                        message: format!("Argumento obrigatório não fornecido: <{}>", #name),
+                    })?
+                }
+            });
+            continue;
+        }
+
+        let mut builder = Vec::new();
+        if let Some(short) = attrs.short {
+            let short = match short {
+                Some(short) => short,
+                None => name.chars().next().expect("nome da flag não vazio"),
+            };
+            builder.push(quote! { flag = flag.short(#short); });
+        }
+        if let Some(ref env) = attrs.env {
+            builder.push(quote! { flag = flag.env(#env); });
+        }
+        if attrs.global {
+            builder.push(quote! { flag = flag.global(true); });
+        }
+        if attrs.allow_hyphen_values {
+            builder.push(quote! { flag = flag.allow_hyphen_values(true); });
+        }
+        if let Some(ref default) = attrs.default_value {
+            check_default_value(ty, default, &name)?;
+            builder.push(quote! {
+                flag = ::cliparser::derive::default_value(flag, #default);
+            });
+        }
+
+        // Campos `Option<T>`, com valor padrão ou com valor de ausência (`bool`,
+        // listas) não são obrigatórios
+        let required = if optional.is_some() || attrs.default_value.is_some() {
+            quote! { false }
+        } else {
+            quote! { <#ty as ::cliparser::FlagArg>::missing().is_none() }
+        };
+
+        code.augment.push(quote! {
+            command = command.add_flag({
+                let mut flag = ::cliparser::derive::flag_for::<#ty>(#name)
+                    .description(#doc)
+                    .required(#required);
+                #(#builder)*
+                flag
+            });
+        });
+
+        let value = quote! { ::cliparser::derive::flag_value::<#ty>(parsed, #name)? };
+        code.init.push(if optional.is_some() {
+            quote! { #ident: #value }
+        } else {
+            quote! {
+                #ident: #value
+                    .or_else(<#ty as ::cliparser::FlagArg>::missing)
+                    .ok_or_else(|| ::cliparser::AppError::RequiredFlagNotProvided {
+                        flag: #name.to_string(),
+                    })?
+            }
+        });
+    }
+
+    Ok(code)
+}
+
+fn named_fields(input: &DeriveInput) -> Result<Option<&FieldsNamed>> {
+    match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => Ok(Some(fields)),
+            Fields::Unit => Ok(None),
+            Fields::Unnamed(_) => Err(Error::new_spanned(
+                input,
+                "#[derive(Parser)] não suporta structs de tupla",
+            )),
+        },
+        _ => Err(Error::new_spanned(
+            input,
+            "#[derive(Parser)] só suporta structs",
+        )),
+    }
+}
+
+fn expand_parser(input: &DeriveInput) -> Result<TokenStream2> {
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let attrs = CommandAttrs::parse(&input.attrs)?;
+
+    let code = match named_fields(input)? {
+        Some(fields) => expand_fields(fields)?,
+        None => FieldsCode {
+            augment: Vec::new(),
+            init: Vec::new(),
+        },
+    };
+    let augment = &code.augment;
+    let init = &code.init;
+
+    let name = match attrs.name {
+        Some(name) => quote! { #name },
+        None => quote! { env!("CARGO_PKG_NAME") },
+    };
+    let version = match attrs.version {
+        Some(version) => quote! { #version },
+        None => quote! { env!("CARGO_PKG_VERSION") },
+    };
+    let about = attrs.about.unwrap_or_else(|| doc_comment(&input.attrs));
+
+    Ok(quote! {
+        impl #impl_generics ::cliparser::Args for #ident #ty_generics #where_clause {
+            #[allow(unused_mut)]
+            fn augment_command(command: ::cliparser::Command) -> ::cliparser::Command {
+                let mut command = command;
+                #(#augment)*
+                command
+            }
+
+            #[allow(unused_variables)]
+            fn from_parsed(
+                parsed: &::cliparser::ParsedArgs,
+            ) -> ::std::result::Result<Self, ::cliparser::AppError> {
+                ::std::result::Result::Ok(Self { #(#init),* })
+            }
+        }
+
+        impl #impl_generics ::cliparser::Parser for #ident #ty_generics #where_clause {
+            fn app() -> ::cliparser::App {
+                let mut app = ::cliparser::App::new(#name, #version).description(#about);
+                // Argumentos vazios constroem o valor (ou geram erro de campo obrigatório)
+                // em vez de exibir a ajuda
+                app.root_command = <Self as ::cliparser::Args>::augment_command(
+                    app.root_command.show_help_on_empty(false),
+                );
+                app
+            }
+        }
+    })
+}
+
+fn expand_subcommand(input: &DeriveInput) -> Result<TokenStream2> {
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let Data::Enum(data) = &input.data else {
+        return Err(Error::new_spanned(
+            input,
+            "#[derive(Subcommand)] só suporta enums",
+        ));
+    };
+
+    let mut augment = Vec::new();
+    let mut arms = Vec::new();
+
+    for variant in &data.variants {
+        let variant_ident = &variant.ident;
+        let attrs = CommandAttrs::parse(&variant.attrs)?;
+        let name = attrs.name.unwrap_or_else(|| kebab_case(variant_ident));
+        let about = attrs.about.unwrap_or_else(|| doc_comment(&variant.attrs));
+        let command = quote! {
+            ::cliparser::Command::new(#name)
+                .description(#about)
+                .show_help_on_empty(false)
+        };
+
+        match &variant.fields {
+            Fields::Unit => {
+                augment.push(quote! { command = command.add_subcommand(#command); });
+                arms.push(quote! { #name => ::std::option::Option::Some(Self::#variant_ident) });
+            }
+            Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {
+                let ty = &fields.unnamed[0].ty;
+                augment.push(quote! {
+                    command = command.add_subcommand(
+                        <#ty as ::cliparser::Args>::augment_command(#command),
+                    );
+                });
+                arms.push(quote! {
+                    #name => ::std::option::Option::Some(Self::#variant_ident(
+                        <#ty as ::cliparser::Args>::from_parsed(matches)?,
+                    ))
+                });
+            }
+            Fields::Named(fields) => {
+                let code = expand_fields(fields)?;
+                let fields_augment = &code.augment;
+                let init = &code.init;
+                augment.push(quote! {
+                    command = command.add_subcommand({
+                        let mut command = #command;
+                        #(#fields_augment)*
+                        command
+                    });
+                });
+                arms.push(quote! {
+                    #name => {
+                        let parsed = matches;
+                        ::std::option::Option::Some(Self::#variant_ident { #(#init),* })
+                    }
+                });
+            }
+            Fields::Unnamed(_) => {
+                return Err(Error::new_spanned(
+                    variant,
+                    "variantes de tupla devem ter exatamente um campo que implemente Args",
+                ));
+            }
+        }
+    }
+
+    Ok(quote! {
+        impl #impl_generics ::cliparser::Subcommand for #ident #ty_generics #where_clause {
+            #[allow(unused_mut)]
+            fn augment_subcommands(command: ::cliparser::Command) -> ::cliparser::Command {
+                let mut command = command;
+                #(#augment)*
+                command
+            }
+
+            #[allow(unused_variables)]
+            fn from_parsed(
+                parsed: &::cliparser::ParsedArgs,
+            ) -> ::std::result::Result<::std::option::Option<Self>, ::cliparser::AppError> {
+                let (::std::option::Option::Some(name), ::std::option::Option::Some(matches)) = (
+                    parsed.subcommand.as_deref(),
+                    parsed.subcommand_matches.as_deref(),
+                ) else {
+                    return ::std::result::Result::Ok(::std::option::Option::None);
+                };
+
+                ::std::result::Result::Ok(match name {
+                    #(#arms,)*
+                    _ => ::std::option::Option::None,
+                })
+            }
+        }
+    })
+}
+
+fn expand_value_enum(input: &DeriveInput) -> Result<TokenStream2> {
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let Data::Enum(data) = &input.data else {
+        return Err(Error::new_spanned(
+            input,
+            "#[derive(ValueEnum)] só suporta enums",
+        ));
+    };
+
+    let mut names = Vec::new();
+    let mut variants = Vec::new();
+
+    for variant in &data.variants {
+        if !matches!(variant.fields, Fields::Unit) {
+            return Err(Error::new_spanned(
+                variant,
+                "#[derive(ValueEnum)] só suporta variantes sem campos",
+            ));
+        }
+
+        let mut name = kebab_case(&variant.ident);
+        for attr in variant
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("value"))
+        {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("name") {
+                    name = meta.value()?.parse::<LitStr>()?.value();
+                    Ok(())
+                } else {
+                    Err(meta.error("opção desconhecida em #[value]"))
+                }
+            })?;
+        }
+
+        names.push(name);
+        variants.push(&variant.ident);
+    }
+
+    Ok(quote! {
+        impl #impl_generics ::cliparser::ValueEnum for #ident #ty_generics #where_clause {
+            fn possible_values() -> ::std::vec::Vec<::std::string::String> {
+                ::std::vec![#(#names.to_string()),*]
+            }
+
+            fn from_value(value: &str) -> ::std::option::Option<Self> {
+                match value {
+                    #(#names => ::std::option::Option::Some(Self::#variants),)*
+                    _ => ::std::option::Option::None,
+                }
+            }
+        }
+
+        impl #impl_generics ::cliparser::FlagArg for #ident #ty_generics #where_clause {
+            fn flag_type() -> ::cliparser::FlagType {
+                ::cliparser::FlagType::String
+            }
+
+            fn possible_values() -> ::std::option::Option<::std::vec::Vec<::std::string::String>> {
+                ::std::option::Option::Some(<Self as ::cliparser::ValueEnum>::possible_values())
+            }
+
+            fn from_flag_value(value: &::cliparser::flag::FlagValue) -> ::std::option::Option<Self> {
+                value
+                    .as_string()
+                    .and_then(<Self as ::cliparser::ValueEnum>::from_value)
+            }
+        }
+    })
+}
@@ -0,0 +1,243 @@
+use cliparser::{AppError, FlagType, Parser, Subcommand, ValueEnum};
+
+/// Gerenciador de tarefas
+#[derive(Parser, Debug, PartialEq)]
+#[command(name = "tasks", version = "1.2.0")]
+struct Cli {
+    /// Modo verboso
+    #[arg(short, global)]
+    verbose: bool,
+
+    /// Arquivo de configuração
+    #[arg(long = "config-file", env = "TASKS_CONFIG")]
+    config: Option<String>,
+
+    #[command(subcommand)]
+    command: Option<Commands>,
+}
+
+#[derive(Subcommand, Debug, PartialEq)]
+enum Commands {
+    /// Adiciona uma tarefa
+    Add {
+        /// Título da tarefa
+        #[arg(positional)]
+        title: String,
+
+        /// Prioridade
+        #[arg(short = 'p', default_value = "normal")]
+        priority: Priority,
+
+        /// Etiquetas
+        tags: Vec<String>,
+    },
+    /// Lista as tarefas
+    List(ListArgs),
+    /// Remove tarefas concluídas
+    #[command(name = "clean")]
+    CleanDone,
+}
+
+#[derive(Parser, Debug, PartialEq)]
+struct ListArgs {
+    /// Quantidade máxima
+    #[arg(short = 'n')]
+    max_count: Option<i64>,
+
+    /// Mostra também as concluídas
+    all: bool,
+}
+
+#[derive(ValueEnum, Debug, PartialEq, Clone, Copy)]
+enum Priority {
+    Low,
+    Normal,
+    #[value(name = "urgent")]
+    VeryHigh,
+}
+
+#[test]
+fn test_app_definition() {
+    let app = Cli::app();
+
+    assert_eq!(app.name, "tasks");
+    assert_eq!(app.version, "1.2.0");
+    assert_eq!(app.description, "Gerenciador de tarefas");
+
+    let verbose = &app.root_command.flags["verbose"];
+    assert_eq!(verbose.short, Some('v'));
+    assert!(verbose.global);
+    assert!(!verbose.required);
+    assert_eq!(verbose.description, "Modo verboso");
+
+    let config = &app.root_command.flags["config-file"];
+    assert_eq!(config.env.as_deref(), Some("TASKS_CONFIG"));
+    assert!(!config.required);
+
+    let add = &app.root_command.subcommands["add"];
+    assert_eq!(add.description, "Adiciona uma tarefa");
+    assert_eq!(add.positional_args[0].name, "title");
+    assert!(add.positional_args[0].required);
+
+    let priority = &add.flags["priority"];
+    assert!(matches!(priority.flag_type, FlagType::String));
+    assert_eq!(
+        priority.possible_values,
+        Some(vec![
+            "low".to_string(),
+            "normal".to_string(),
+            "urgent".to_string()
+        ])
+    );
+    assert!(priority.default_value.is_some());
+
+    let list = &app.root_command.subcommands["list"];
+    assert_eq!(list.description, "Lista as tarefas");
+    assert!(matches!(
+        list.flags["max-count"].flag_type,
+        FlagType::Integer
+    ));
+
+    assert!(app.root_command.subcommands.contains_key("clean"));
+    assert!(app.validate().is_ok());
+}
+
+#[test]
+fn test_parse_struct_variant() {
+    let cli = Cli::try_parse_from([
+        "add",
+        "Escrever docs",
+        "-p",
+        "urgent",
+        "--tags",
+        "docs",
+        "--tags",
+        "rust",
+        "-v",
+    ])
+    .unwrap();
+
+    assert_eq!(
+        cli,
+        Cli {
+            verbose: true,
+            config: None,
+            command: Some(Commands::Add {
+                title: "Escrever docs".to_string(),
+                priority: Priority::VeryHigh,
+                tags: vec!["docs".to_string(), "rust".to_string()],
+            }),
+        }
+    );
+}
+
+#[test]
+fn test_defaults_and_missing_values() {
+    let cli = Cli::try_parse_from(["add", "Tarefa"]).unwrap();
+
+    assert_eq!(
+        cli.command,
+        Some(Commands::Add {
+            title: "Tarefa".to_string(),
+            priority: Priority::Normal,
+            tags: Vec::new(),
+        })
+    );
+    assert!(!cli.verbose);
+}
+
+#[test]
+fn test_parse_tuple_and_unit_variants() {
+    let cli =
+        Cli::try_parse_from(["--config-file", "tasks.toml", "list", "-n", "5", "--all"]).unwrap();
+    assert_eq!(cli.config.as_deref(), Some("tasks.toml"));
+    assert_eq!(
+        cli.command,
+        Some(Commands::List(ListArgs {
+            max_count: Some(5),
+            all: true,
+        }))
+    );
+
+    let cli = Cli::try_parse_from(["clean"]).unwrap();
+    assert_eq!(cli.command, Some(Commands::CleanDone));
+}
+
+#[test]
+fn test_invalid_value_enum() {
+    let error = Cli::try_parse_from(["add", "Tarefa", "--priority", "hight"]).unwrap_err();
+    assert!(matches!(error, AppError::InvalidFlagValue { .. }));
+}
+
+#[test]
+fn test_value_enum() {
+    assert_eq!(Priority::possible_values(), vec!["low", "normal", "urgent"]);
+    assert_eq!(Priority::from_value("urgent"), Some(Priority::VeryHigh));
+    assert_eq!(Priority::from_value("very-high"), None);
+}
+
+#[test]
+fn test_subcommand_from_parsed_without_subcommand() {
+    let parsed = Cli::app().parse(["-v"]).unwrap();
+    assert_eq!(
+        <Commands as Subcommand>::from_parsed(&parsed).unwrap(),
+        None
+    );
+}
+
+#[derive(Parser)]
+#[allow(dead_code)]
+struct InvalidDefault {
+    #[arg(default_value = "medium")]
+    priority: Priority,
+}
+
+#[test]
+fn test_invalid_default_value_enum_fails_validation() {
+    let app = InvalidDefault::app();
+    assert!(matches!(
+        app.validate().unwrap_err(),
+        AppError::ConfigurationError { message } if message.contains("medium")
+    ));
+    assert!(matches!(
+        InvalidDefault::try_parse_from(["--priority", "low"]),
+        Err(AppError::ConfigurationError { .. })
+    ));
+}
+
+#[test]
+fn test_empty_args_build_value() {
+    let cli = Cli::try_parse_from(Vec::<&str>::new()).unwrap();
+    assert_eq!(
+        cli,
+        Cli {
+            verbose: false,
+            config: None,
+            command: None,
+        }
+    );
+    assert!(matches!(
+        Cli::try_parse_from(["add"]),
+        Err(AppError::NotEnoughArguments { .. })
+    ));
+}
+
+#[test]
+fn test_help_requested() {
+    assert_eq!(Cli::try_parse_from(["--help"]), Err(AppError::HelpRequested));
+    assert_eq!(Cli::try_parse_from(["add", "--help"]), Err(AppError::HelpRequested));
+    assert_eq!(Cli::try_parse_from(["list", "-h"]), Err(AppError::HelpRequested));
+}
+
+#[derive(ValueEnum, Debug, PartialEq, Clone, Copy)]
+enum Backend {
+    HTTPServer,
+    GrpcApi,
+    S3,
+}
+
+#[test]
+fn test_kebab_case_keeps_acronyms_together() {
+    assert_eq!(Backend::possible_values(), vec!["httpserver", "grpc-api", "s3"]);
+    assert_eq!(Backend::from_value("httpserver"), Some(Backend::HTTPServer));
+}
@@ -1,4 +1,4 @@
-use cliparser::{App, Command, Flag, FlagType, ParsedArgs};
+use cliparser::{App, AppError, Command, Context, Flag, FlagType};
 
 fn main() {
     let app = App::new("exemplo-basico", "1.0.0")
@@ -30,6 +30,7 @@ fn main() {
                         ])
                         .default_value(cliparser::flag::FlagValue::String("olá".to_string()))
                 )
+                .action(handle_hello_command)
 
         )
         .add_command(
@@ -43,6 +44,7 @@ fn main() {
                                 .description("Números a serem somados")
                                 .required(true)
                         )
+                        .action(handle_calc_command)
                 )
                 .add_subcommand(
                     Command::new("multiply")
@@ -52,41 +54,23 @@ fn main() {
                                 .description("Números a serem multiplicados")
                                 .required(true)
                         )
+                        .action(handle_calc_command)
                 )
         );
 
-    match app.run_from_env() {
-        Ok(parsed) => {
-            if parsed.help_requested {
-                return;
-            }
-
-
-            match parsed.subcommand.as_deref() {
-                Some("hello") => handle_hello_command(&parsed),
-                Some("calc") => handle_calc_command(&parsed),
-                _ => {
-                    cliparser::ui::ColoredUI::show_warning("Nenhum comando especificado");
-                    cliparser::ui::ColoredUI::show_info("Use --help para ver os comandos disponíveis");
-                }
-            }
-        }
-        Err(_) => {
-            std::process::exit(1);
-        }
-    }
+    std::process::exit(app.dispatch_from_env());
 }
 
-fn handle_hello_command(parsed: &cliparser::ParsedArgs) {
-    let name = parsed.get_flag("name")
+fn handle_hello_command(ctx: &Context) -> Result<(), AppError> {
+    let name = ctx.get_flag("name")
         .and_then(|v| v.as_string())
         .unwrap_or("Mundo");
 
-    let times = parsed.get_flag("times")
+    let times = ctx.get_flag("times")
         .and_then(|v| v.as_integer())
         .unwrap_or(1);
 
-    let greeting = parsed.get_flag("greeting")
+    let greeting = ctx.get_flag("greeting")
     .and_then(|v| v.as_string())
         .unwrap_or("olá");
 
@@ -98,22 +82,24 @@ fn handle_hello_command(parsed: &cliparser::ParsedArgs) {
             cliparser::ui::ColoredUI::show_success(&message);
         }
     }
+
+    Ok(())
 }
 
-pub fn handle_calc_command(parsed: &ParsedArgs) {
-    if let Some(numbers) = parsed.get_flag("numbers") {
-        if let Some(number_list) = numbers.as_integer_list() {
-            match parsed.command.as_str() {
-                "add" => {
-                    let sum: i64 = number_list.iter().sum();
-                    cliparser::ui::ColoredUI::show_success(&format!("Soma: {}", sum));
-                }
-                "multiply" => {
-                    let product: i64 = number_list.iter().product();
-                    cliparser::ui::ColoredUI::show_success(&format!("Produto: {}", product));
-                }
-                _ => {}
-            }
+pub fn handle_calc_command(ctx: &Context) -> Result<(), AppError> {
+    let number_list = ctx.get_many::<i64>("numbers")?.unwrap_or_default();
+
+    match ctx.path.as_slice() {
+        ["calc", "add"] => {
+            let sum: i64 = number_list.iter().sum();
+            cliparser::ui::ColoredUI::show_success(&format!("Soma: {}", sum));
+        }
+        ["calc", "multiply"] => {
+            let product: i64 = number_list.iter().product();
+            cliparser::ui::ColoredUI::show_success(&format!("Produto: {}", product));
         }
+        _ => {}
     }
-}
\ No newline at end of file
+
+    Ok(())
+}
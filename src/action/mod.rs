@@ -0,0 +1,98 @@
+//! Ações dos comandos (`Command::action`), executadas por `App::dispatch`
+
+use crate::flag::FlagValue;
+use crate::{AppError, ParsedArgs};
+use std::any::Any;
+use std::fmt;
+use std::sync::Arc;
+
+/// Código de saída quando a ação termina sem erro ou a ajuda é exibida
+pub const EXIT_SUCCESS: i32 = 0;
+
+/// Código de saída quando a ação retorna um erro
+pub const EXIT_FAILURE: i32 = 1;
+
+/// Código de saída para erros de uso: parsing inválido ou comando sem ação
+pub const EXIT_USAGE: i32 = 2;
+
+/// O que a ação de um comando recebe de `App::dispatch`
+pub struct Context<'a> {
+    /// Resultado completo do parsing, a partir da raiz
+    pub parsed: &'a ParsedArgs,
+    /// Caminho do comando executado, ex: `["calc", "add"]`
+    pub path: Vec<&'a str>,
+    state: Option<&'a (dyn Any + Send + Sync)>,
+}
+
+impl<'a> Context<'a> {
+    pub(crate) fn new(parsed: &'a ParsedArgs, state: Option<&'a (dyn Any + Send + Sync)>) -> Self {
+        Self {
+            parsed,
+            path: parsed.command_path(),
+            state,
+        }
+    }
+
+    /// Nível de `parsed` do comando executado, com seus argumentos posicionais.
+    /// Flags globais ficam no nível do comando que as define e não aparecem aqui;
+    /// para ler flags use `get_flag`, `get` ou `get_many`
+    pub fn matches(&self) -> &'a ParsedArgs {
+        self.parsed.deepest_matches()
+    }
+
+    /// Valor da flag, buscado do comando executado até a raiz, incluindo flags globais
+    pub fn get_flag(&self, flag: &str) -> Option<&'a FlagValue> {
+        self.parsed.get_flag(flag)
+    }
+
+    /// Valor da flag convertido para `T`, buscado como em `get_flag`
+    pub fn get<T: Any + Clone>(&self, flag: &str) -> Result<Option<T>, AppError> {
+        self.parsed.get(flag)
+    }
+
+    /// Todos os valores da flag convertidos para `T`, buscados como em `get_flag`
+    pub fn get_many<T: Any + Clone>(&self, flag: &str) -> Result<Option<Vec<T>>, AppError> {
+        self.parsed.get_many(flag)
+    }
+
+    /// Estado registrado com `App::state`, se for do tipo `T`
+    pub fn state<T: Any>(&self) -> Option<&'a T> {
+        self.state.and_then(|state| state.downcast_ref())
+    }
+}
+
+type ActionFn = dyn Fn(&Context) -> Result<(), AppError> + Send + Sync;
+
+/// Ação de um comando, guardada em `Command::action`
+#[derive(Clone)]
+pub struct Action(Arc<ActionFn>);
+
+impl Action {
+    pub fn new<F>(action: F) -> Self
+    where
+        F: Fn(&Context) -> Result<(), AppError> + Send + Sync + 'static,
+    {
+        Self(Arc::new(action))
+    }
+
+    pub fn call(&self, context: &Context) -> Result<(), AppError> {
+        (self.0)(context)
+    }
+}
+
+impl fmt::Debug for Action {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Action")
+    }
+}
+
+/// Código de saída para o erro retornado por uma ação
+pub fn exit_code(error: &AppError) -> i32 {
+    match error {
+        AppError::CommandFailed { code, .. } => *code,
+        _ => EXIT_FAILURE,
+    }
+}
+
+#[cfg(test)]
+mod tests;
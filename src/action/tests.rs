@@ -0,0 +1,97 @@
+use super::*;
+use crate::{App, Command, Flag, FlagType};
+use std::sync::Mutex;
+
+#[derive(Default)]
+struct Calls(Mutex<Vec<String>>);
+
+fn test_app() -> App {
+    App::new("app", "1.0.0")
+        .state(Calls::default())
+        .add_global_flag(Flag::new("verbose", FlagType::Bool).short('v'))
+        .add_command(
+            Command::new("calc").add_subcommand(
+                Command::new("add")
+                    .add_flag(Flag::new("numbers", FlagType::IntegerList).required(true))
+                    .action(|ctx| {
+                        let numbers = ctx.get_many::<i64>("numbers")?.unwrap_or_default();
+                        let verbose = ctx.get::<bool>("verbose")?.unwrap_or(false);
+                        let calls = ctx.state::<Calls>().expect("estado registrado");
+                        calls.0.lock().unwrap().push(format!(
+                            "{} {} {}",
+                            ctx.path.join(" "),
+                            numbers.iter().sum::<i64>(),
+                            verbose
+                        ));
+                        Ok(())
+                    }),
+            ),
+        )
+        .add_command(Command::new("fail").show_help_on_empty(false).action(|_| {
+            Err(AppError::CommandFailed {
+                message: "falhou".to_string(),
+                code: 42,
+            })
+        }))
+        .add_command(
+            Command::new("io")
+                .show_help_on_empty(false)
+                .action(|_| Err(AppError::IoError("disco cheio".to_string()))),
+        )
+}
+
+fn calls(app: &App) -> Vec<String> {
+    let state = app
+        .state
+        .as_deref()
+        .and_then(|state| state.downcast_ref::<Calls>());
+    state.unwrap().0.lock().unwrap().clone()
+}
+
+#[test]
+fn test_dispatch_runs_deepest_action_with_context() {
+    let app = test_app();
+
+    assert_eq!(
+        app.dispatch(["-v", "calc", "add", "--numbers", "1,2,3"]),
+        EXIT_SUCCESS
+    );
+    assert_eq!(calls(&app), vec!["calc add 6 true"]);
+}
+
+#[test]
+fn test_dispatch_exit_codes() {
+    let app = test_app();
+
+    assert_eq!(app.dispatch(["fail"]), 42);
+    assert_eq!(app.dispatch(["io"]), EXIT_FAILURE);
+    assert_eq!(app.dispatch(["calc", "add", "--numbers", "x"]), EXIT_USAGE);
+    assert_eq!(app.dispatch(["calc", "add", "--help"]), EXIT_SUCCESS);
+    assert!(calls(&app).is_empty());
+}
+
+#[test]
+fn test_dispatch_command_without_action_shows_help() {
+    let app = test_app();
+    assert_eq!(app.dispatch(["-v"]), EXIT_USAGE);
+}
+
+#[test]
+fn test_context_without_state() {
+    let parsed = crate::ParsedArgs::new("app".to_string());
+    let context = Context::new(&parsed, None);
+
+    assert!(context.path.is_empty());
+    assert!(context.state::<Calls>().is_none());
+}
+
+#[test]
+fn test_context_reads_global_flags() {
+    let parsed = test_app().parse(["calc", "add", "--numbers", "1", "-v"]).unwrap();
+    let context = Context::new(&parsed, None);
+
+    assert!(!context.matches().flags.contains_key("verbose"));
+    assert_eq!(context.get_flag("verbose"), Some(&FlagValue::Bool(true)));
+    assert_eq!(context.get::<bool>("verbose").unwrap(), Some(true));
+    assert_eq!(context.get_many::<i64>("numbers").unwrap(), Some(vec![1]));
+}
@@ -1,13 +1,42 @@
+use crate::action::{self, Context, EXIT_SUCCESS, EXIT_USAGE};
+use crate::command::PositionalArg;
+use crate::completions::{self, Candidate, Shell};
+use crate::config::{ConfigFormat, ConfigSource, ConfigValues};
+use crate::docs::{self, DocFormat};
 use crate::parser::CLIParser;
 use crate::ui::ColoredUI;
-use crate::{AppError, Command, Flag, ParsedArgs};
+use crate::flag::FlagValue;
+use crate::man;
+use crate::spec;
+use crate::suggestions::did_you_mean;
+use crate::{AppError, Command, EnvSource, Flag, FlagType, ParsedArgs};
+use std::any::Any;
 use std::env;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Nome da flag global adicionada por `App::explain_config`
+pub const EXPLAIN_CONFIG_FLAG: &str = "explain-config";
+
+/// Nome do subcomando adicionado por `App::completion_command`
+pub const COMPLETION_COMMAND: &str = "completion";
+
+/// Subcomando oculto adicionado por `App::dynamic_completion`, chamado pelos scripts
+/// do shell como `<app> __complete <índice> -- <palavras>`
+pub const COMPLETE_COMMAND: &str = "__complete";
+
 #[derive(Debug, Clone)]
 pub struct App {
     pub name: String,
     pub version: String,
     pub description: String,
     pub root_command: Command,
+    pub env_source: EnvSource,
+    pub config_sources: Vec<ConfigSource>,
+    pub completion_command: bool,
+    pub dynamic_completion: bool,
+    /// Estado compartilhado entregue às ações em `Context::state`
+    pub state: Option<Arc<dyn Any + Send + Sync>>,
 }
 
 impl App {
@@ -24,9 +53,25 @@ impl App {
             version: version.into(),
             description: String::new(),
             root_command,
+            env_source: EnvSource::default(),
+            config_sources: Vec::new(),
+            completion_command: false,
+            dynamic_completion: false,
+            state: None,
         }
     }
 
+    /// Monta a aplicação a partir de uma especificação (mesmo formato de
+    /// `to_spec_json`) e a valida. Veja `spec::from_str`
+    pub fn from_spec_str(contents: &str, format: ConfigFormat) -> Result<Self, AppError> {
+        spec::from_str(contents, format)
+    }
+
+    /// Igual a `from_spec_str`, lendo um arquivo .json, .toml, .yaml ou .yml
+    pub fn from_spec_file(path: impl AsRef<Path>) -> Result<Self, AppError> {
+        spec::from_file(path)
+    }
+
     pub fn description(mut self, description: impl Into<String>) -> Self {
         self.description = description.into();
         self
@@ -37,11 +82,122 @@ impl App {
         self
     }
 
+    /// Adiciona uma flag aceita pela aplicação e por todos os subcomandos
     pub fn add_global_flag(mut self, flag: Flag) -> Self {
-        self.root_command = self.root_command.add_flag(flag);
+        self.root_command = self.root_command.add_flag(flag.global(true));
+        self
+    }
+
+    /// Define de onde as flags com `Flag::env` leem variáveis de ambiente
+    pub fn env_source(mut self, env_source: EnvSource) -> Self {
+        self.env_source = env_source;
+        self
+    }
+
+    /// Carrega valores de flags de um arquivo de configuração (.toml, .json, .yaml),
+    /// ignorado se não existir. Arquivos adicionados depois têm precedência
+    pub fn config_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.config_sources.push(ConfigSource::File(path.into()));
+        self
+    }
+
+    /// Carrega `file_name` do diretório de configuração do usuário
+    /// (`$XDG_CONFIG_HOME/<nome da app>/` ou `~/.config/<nome da app>/`), com
+    /// precedência menor que os demais arquivos
+    pub fn user_config_file(mut self, file_name: impl Into<String>) -> Self {
+        self.config_sources.push(ConfigSource::UserConfig(file_name.into()));
         self
     }
 
+    /// Usa o valor da flag global `flag_name` (ex: `--config caminho`) como
+    /// arquivo de configuração de maior precedência
+    pub fn config_flag(mut self, flag_name: impl Into<String>) -> Self {
+        self.config_sources.push(ConfigSource::Flag(flag_name.into()));
+        self
+    }
+
+    /// Adiciona a flag global `--explain-config`, que faz `run` exibir a origem
+    /// de cada valor de flag (linha de comando, ambiente, arquivo ou padrão)
+    pub fn explain_config(self, enabled: bool) -> Self {
+        if !enabled {
+            return self;
+        }
+
+        self.add_global_flag(
+            Flag::new(EXPLAIN_CONFIG_FLAG, FlagType::Bool)
+                .description("Mostra de onde veio o valor de cada flag"),
+        )
+    }
+
+    /// Adiciona o subcomando `completion <shell>`, que faz `run` imprimir o script
+    /// de autocompletar para bash, zsh, fish, elvish ou powershell
+    pub fn completion_command(mut self, enabled: bool) -> Self {
+        if !enabled {
+            return self;
+        }
+
+        let shells: Vec<&str> = Shell::ALL.iter().map(|shell| shell.name()).collect();
+
+        self.completion_command = true;
+        self.add_command(
+            Command::new(COMPLETION_COMMAND)
+                .description("Gera o script de autocompletar para o shell")
+                .add_positional_arg(
+                    PositionalArg::new("shell").description(shells.join(", ")),
+                ),
+        )
+    }
+
+    /// Adiciona o subcomando oculto `__complete`, que imprime as sugestões de
+    /// `App::complete`. Com ele, `completion <shell>` passa a gerar scripts que
+    /// consultam o binário a cada Tab, permitindo sugestões calculadas em tempo
+    /// de execução (`Flag::value_completer`)
+    pub fn dynamic_completion(mut self, enabled: bool) -> Self {
+        if !enabled {
+            return self;
+        }
+
+        self.dynamic_completion = true;
+        self.add_command(
+            Command::new(COMPLETE_COMMAND)
+                .hidden(true)
+                .add_positional_arg(PositionalArg::new("cursor")),
+        )
+    }
+
+    /// Gera o script de autocompletar da aplicação para `shell`, estático ou,
+    /// com `dynamic_completion`, delegando ao subcomando `__complete`
+    pub fn generate_completions(&self, shell: Shell) -> String {
+        if self.dynamic_completion {
+            completions::generate_dynamic(self, shell)
+        } else {
+            completions::generate(self, shell)
+        }
+    }
+
+    /// Sugestões de autocompletar para a palavra em `cursor_index` de `args`
+    pub fn complete(&self, args: &[String], cursor_index: usize) -> Vec<Candidate> {
+        completions::complete(self, args, cursor_index)
+    }
+
+    /// Escreve uma página de manual (roff, seção 1) por comando em `dir`,
+    /// ex: `app.1`, `app-calc.1`, `app-calc-add.1`
+    pub fn write_man_pages(&self, dir: impl AsRef<Path>) -> Result<Vec<PathBuf>, AppError> {
+        man::write_to_dir(self, dir)
+    }
+
+    /// Escreve em `dir` a documentação de referência (Markdown ou HTML), uma
+    /// página por comando, ex: `app.md`, `app-calc.md`
+    pub fn write_docs(&self, dir: impl AsRef<Path>, format: DocFormat) -> Result<Vec<PathBuf>, AppError> {
+        docs::write_to_dir(self, dir, format)
+    }
+
+    /// Especificação completa da CLI em JSON (comandos, flags e argumentos), com
+    /// versão do formato em `spec_version`. Veja `spec::AppSpec`
+    pub fn to_spec_json(&self) -> String {
+        spec::to_json(self)
+    }
+
      pub fn show_help_on_empty(mut self, show_help_on_empty: bool) -> Self {
         self.root_command = self.root_command.show_help_on_empty(show_help_on_empty);
         self
@@ -57,51 +213,206 @@ impl App {
     }
 
     fn parse_from_args(&self, args: Vec<String>) -> Result<ParsedArgs, AppError> {
-        CLIParser::parse(&self.root_command, args)
+        let config = self.load_config(&args)?;
+        CLIParser::parse_with_config(&self.root_command, args, &self.env_source, &config)
+    }
+
+    /// Carrega as camadas de configuração registradas para os argumentos fornecidos
+    pub fn load_config(&self, args: &[String]) -> Result<ConfigValues, AppError> {
+        ConfigValues::load(
+            &self.name,
+            &self.config_sources,
+            &self.root_command,
+            args,
+            &self.env_source,
+        )
     }
 
     pub fn validate(&self) -> Result<(), AppError> {
-        self.validate_command(&self.root_command)
+        self.validate_with_path()
+            .map_err(|(_, message)| AppError::ConfigurationError { message })
+    }
+
+    /// Igual a `validate`, retornando o caminho do problema no formato da
+    /// especificação (ex: `commands.calc.flags.numbers`) e a mensagem de erro
+    pub(crate) fn validate_with_path(&self) -> Result<(), (String, String)> {
+        for source in &self.config_sources {
+            if let ConfigSource::Flag(flag_name) = source
+                && !self.root_command.flags.contains_key(flag_name)
+            {
+                return Err((
+                    format!("flags.{}", flag_name),
+                    format!("Flag de configuração não encontrada: {}", flag_name),
+                ));
+            }
+        }
+
+        self.validate_command(&self.root_command, &[], "")
     }
 
-    fn validate_command(&self, command: &Command) -> Result<(), AppError> {
+    fn validate_command(
+        &self,
+        command: &Command,
+        inherited: &[&Flag],
+        path: &str,
+    ) -> Result<(), (String, String)> {
         let mut flag_names = std::collections::HashSet::new();
         let mut short_names = std::collections::HashSet::new();
 
-        for flag in command.flags.values() {
+        for flag in command.get_flags_sorted() {
+            let flag_path = spec_path(path, "flags", &flag.name);
+            let error = |message: String| Err((flag_path.clone(), message));
+
+            if let Some(global) = inherited
+                .iter()
+                .find(|global| global.name == flag.name || (flag.short.is_some() && global.short == flag.short))
+            {
+                return error(format!(
+                    "Flag '{}' do comando '{}' conflita com a flag global '{}'",
+                    flag.name, command.name, global.name
+                ));
+            }
+
             if !flag_names.insert(flag.name.clone()) {
-                return Err(AppError::ConfigurationError {
-                    message: format!("Flag duplicada encontrada: {}", flag.name),
-                });
+                return error(format!("Flag duplicada encontrada: {}", flag.name));
             }
 
             if let Some(short) = flag.short {
                 if !short_names.insert(short) {
-                    return Err(AppError::ConfigurationError {
-                        message: format!("Flag curta duplicada encontrada: {}", short),
-                    });
+                    return error(format!("Flag curta duplicada encontrada: {}", short));
                 }
             }
 
+            if flag.negatable && !matches!(flag.flag_type, FlagType::Bool) {
+                return error(format!("Flag '{}' só pode ser negável se for do tipo bool", flag.name));
+            }
+
+            if !flag.flag_type.is_list() && (flag.num_values.is_some() || flag.value_delimiter.is_some()) {
+                return error(format!(
+                    "Flag '{}' do tipo {} não aceita num_values nem value_delimiter",
+                    flag.name,
+                    flag.flag_type.description()
+                ));
+            }
+
+            if let Some(range) = flag.num_values
+                && range.max.is_some_and(|max| max == 0 || max < range.min)
+            {
+                return error(format!(
+                    "Flag '{}' tem num_values inválido: máximo menor que o mínimo ou igual a zero",
+                    flag.name
+                ));
+            }
+
+            if flag.default_missing_value.is_some() && !flag.flag_type.takes_value() {
+                return error(format!(
+                    "Flag '{}' do tipo {} não recebe valor e não pode ter valor opcional",
+                    flag.name,
+                    flag.flag_type.description()
+                ));
+            }
+
+            if let Some(negated) = flag.negated_name()
+                && command.flags.contains_key(&negated)
+            {
+                return error(format!(
+                    "Flag '{}' é negável e conflita com a flag '{}'",
+                    flag.name, negated
+                ));
+            }
+
+            if let Some(ref default) = flag.default_value
+                && !flag.accepts_value(default)
+            {
+                return error(format!(
+                    "Flag '{}' tem valor padrão inválido para o tipo {}: {}",
+                    flag.name,
+                    flag.flag_type.description(),
+                    default
+                ));
+            }
+
             if flag.required && flag.default_value.is_some() {
-                return Err(AppError::ConfigurationError {
-                    message: format!(
-                        "Flag '{}' não pode ser obrigatória e ter valor padrão",
-                        flag.name
-                    ),
-                });
+                return error(format!(
+                    "Flag '{}' não pode ser obrigatória e ter valor padrão",
+                    flag.name
+                ));
+            }
+
+            let relationships = [
+                ("conflicts_with", &flag.conflicts_with),
+                ("requires", &flag.requires),
+                ("required_unless", &flag.required_unless),
+            ];
+            for (relationship, names) in relationships {
+                if let Some(missing) = names.iter().find(|name| !command.flags.contains_key(*name)) {
+                    return error(format!(
+                        "Flag '{}' referencia em {} a flag '{}', que não existe no comando '{}'",
+                        flag.name, relationship, missing, command.name
+                    ));
+                }
+            }
+
+            if let Some(ref target) = flag.decrements {
+                let counts = |flag: &Flag| matches!(flag.flag_type, FlagType::Count);
+                match command.flags.get(target) {
+                    Some(target_flag) if counts(flag) && counts(target_flag) => {}
+                    Some(_) => {
+                        return error(format!(
+                            "Flag '{}' só pode diminuir '{}' se ambas forem do tipo count",
+                            flag.name, target
+                        ));
+                    }
+                    None => {
+                        return error(format!(
+                            "Flag '{}' referencia em decrements a flag '{}', que não existe no comando '{}'",
+                            flag.name, target, command.name
+                        ));
+                    }
+                }
             }
         }
 
+        let mut group_names = std::collections::HashSet::new();
+        for (index, group) in command.groups.iter().enumerate() {
+            let groups_path = if path.is_empty() {
+                "groups".to_string()
+            } else {
+                format!("{}.groups", path)
+            };
+            let error = |message: String| Err((format!("{}[{}]", groups_path, index), message));
+
+            if !group_names.insert(group.name.clone()) {
+                return error(format!("Grupo duplicado encontrado: {}", group.name));
+            }
+
+            if group.flags.is_empty() {
+                return error(format!("Grupo '{}' não tem flags", group.name));
+            }
+
+            if let Some(missing) = group.flags.iter().find(|name| !command.flags.contains_key(*name)) {
+                return error(format!(
+                    "Grupo '{}' referencia a flag '{}', que não existe no comando '{}'",
+                    group.name, missing, command.name
+                ));
+            }
+        }
+
+        let mut inherited = inherited.to_vec();
+        inherited.extend(command.flags.values().filter(|flag| flag.global));
+
         let mut subcommand_names = std::collections::HashSet::new();
-        for subcommand in command.subcommands.values() {
+        for subcommand in command.get_subcommands_sorted() {
+            let subcommand_path = spec_path(path, "commands", &subcommand.name);
+
             if !subcommand_names.insert(subcommand.name.clone()) {
-                return Err(AppError::ConfigurationError {
-                    message: format!("Subcomando duplicado encontrado: {}", subcommand.name),
-                });
+                return Err((
+                    subcommand_path,
+                    format!("Subcomando duplicado encontrado: {}", subcommand.name),
+                ));
             }
 
-            self.validate_command(subcommand)?;
+            self.validate_command(subcommand, &inherited, &subcommand_path)?;
         }
 
         Ok(())
@@ -112,19 +423,25 @@ impl App {
         I: IntoIterator<Item = S>,
         S: AsRef<str>,
     {
-        match self.parse(args) {
-            Ok(parsed) => {
-                if parsed.help_requested {
-                    self.show_help(&parsed);
-                    return Ok(parsed);
-                }
-                Ok(parsed)
+        let args: Vec<String> = args.into_iter().map(|s| s.as_ref().to_string()).collect();
+
+        let result = match args.split_first() {
+            Some((first, rest)) if self.dynamic_completion && first == COMPLETE_COMMAND => {
+                self.run_complete(rest)
             }
+            _ => self
+                .parse_from_args(args)
+                .and_then(|parsed| self.handle_builtins(parsed)),
+        };
+
+        match result {
+            Ok(parsed) => Ok(parsed),
             Err(error) => {
                 ColoredUI::show_error(&error);
                 if let AppError::CommandNotFound { .. }
                 | AppError::SubcommandNotFound { .. }
-                | AppError::UnknownFlag { .. } = error
+                | AppError::UnknownFlag { .. }
+                | AppError::UnknownFlagInCluster { .. } = error
                 {
                     println!();
                     ColoredUI::show_info("Use --help para obter ajuda");
@@ -134,18 +451,83 @@ impl App {
         }
     }
 
+    /// Trata ajuda, `--explain-config` e `completion <shell>` antes de devolver
+    /// os argumentos para a aplicação
+    fn handle_builtins(&self, parsed: ParsedArgs) -> Result<ParsedArgs, AppError> {
+        if parsed.help_requested {
+            self.show_help(&parsed);
+            return Ok(parsed);
+        }
+
+        if self.completion_command && parsed.command_path() == [COMPLETION_COMMAND] {
+            print!("{}", self.generate_completions(self.completion_shell(&parsed)?));
+            return Ok(parsed);
+        }
+
+        if parsed.get_flag(EXPLAIN_CONFIG_FLAG) == Some(&FlagValue::Bool(true)) {
+            ColoredUI::show_value_sources(&self.name, &parsed);
+        }
+
+        Ok(parsed)
+    }
+
+    /// Trata `__complete <índice> -- <palavras>` sem passar pelo parsing normal, para
+    /// que flags obrigatórias ou palavras incompletas não impeçam as sugestões
+    fn run_complete(&self, args: &[String]) -> Result<ParsedArgs, AppError> {
+        let cursor = args.first().map(String::as_str).unwrap_or_default();
+        let cursor_index = cursor.parse::<usize>().map_err(|_| AppError::InvalidArgumentValue {
+            arg: "cursor".to_string(),
+            value: cursor.to_string(),
+            expected: "índice da palavra sendo completada".to_string(),
+            suggestions: Vec::new(),
+        })?;
+
+        let words = match args.get(1..) {
+            Some([terminator, words @ ..]) if terminator == "--" => words,
+            Some(words) => words,
+            None => &[],
+        };
+
+        for candidate in self.complete(words, cursor_index) {
+            println!("{}\t{}", candidate.value, candidate.description);
+        }
+
+        let mut parsed = ParsedArgs::new(self.name.clone());
+        parsed.subcommand = Some(COMPLETE_COMMAND.to_string());
+        parsed.subcommand_matches = Some(Box::new(ParsedArgs::new(COMPLETE_COMMAND.to_string())));
+        Ok(parsed)
+    }
+
+    fn completion_shell(&self, parsed: &ParsedArgs) -> Result<Shell, AppError> {
+        let value = parsed.get_arg(0).map(String::as_str).unwrap_or_default();
+        let names: Vec<&str> = Shell::ALL.iter().map(|shell| shell.name()).collect();
+
+        Shell::from_name(value).ok_or_else(|| AppError::InvalidArgumentValue {
+            arg: "shell".to_string(),
+            value: value.to_string(),
+            expected: names.join(", "),
+            suggestions: did_you_mean(value, names.iter().copied()),
+        })
+    }
+
     fn show_help(&self, parsed: &ParsedArgs) {
-        let command = if let Some(ref subcommmand_name) = parsed.subcommand {
-            if let Some(subcommand) = self.root_command.subcommands.get(subcommmand_name) {
-                subcommand
-            } else {
-                &self.root_command
-            }
+        let path = parsed.command_path();
+        let command = self.resolve_command(&path);
+
+        let description = if path.is_empty() {
+            &self.description
         } else {
-            &self.root_command
+            &command.description
         };
 
-        ColoredUI::show_help(&self.name, &self.version, &self.description, command);
+        let global_flags = self.root_command.inherited_global_flags(&path);
+        ColoredUI::show_help(&self.name, &self.version, description, &path, command, &global_flags);
+    }
+
+    fn resolve_command(&self, path: &[&str]) -> &Command {
+        self.root_command
+            .get_subcommand_by_path(path)
+            .unwrap_or(&self.root_command)
     }
 
     pub fn get_info(&self) -> AppInfo {
@@ -180,9 +562,66 @@ impl App {
         self.run(args)
     }
 
+    /// Estado compartilhado (configuração, conexões, ...) entregue às ações de
+    /// `Command::action`, lido com `Context::state::<T>()`
+    pub fn state<T: Any + Send + Sync>(mut self, state: T) -> Self {
+        self.state = Some(Arc::new(state));
+        self
+    }
+
+    /// Faz o parsing com `run` e executa a ação do comando mais profundo encontrado.
+    /// Retorna o código de saída: `EXIT_SUCCESS` se a ação terminou sem erro ou só
+    /// a ajuda foi exibida, `EXIT_USAGE` em erros de parsing ou se o comando não tem
+    /// ação (sua ajuda é exibida) e, se a ação falhar, o código de
+    /// `AppError::CommandFailed` ou `EXIT_FAILURE`, após exibir o erro
+    pub fn dispatch<I, S>(&self, args: I) -> i32
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let Ok(parsed) = self.run(args) else {
+            return EXIT_USAGE;
+        };
+
+        let path = parsed.command_path();
+        let builtin = (self.completion_command && path == [COMPLETION_COMMAND])
+            || (self.dynamic_completion && path == [COMPLETE_COMMAND]);
+        if parsed.help_requested || builtin {
+            return EXIT_SUCCESS;
+        }
+
+        let Some(handler) = &self.resolve_command(&path).action else {
+            self.show_help(&parsed);
+            return EXIT_USAGE;
+        };
+
+        match handler.call(&Context::new(&parsed, self.state.as_deref())) {
+            Ok(()) => EXIT_SUCCESS,
+            Err(error) => {
+                ColoredUI::show_error(&error);
+                action::exit_code(&error)
+            }
+        }
+    }
+
+    /// `dispatch` com os argumentos do processo, para uso em
+    /// `std::process::exit(app.dispatch_from_env())`
+    pub fn dispatch_from_env(&self) -> i32 {
+        self.dispatch(env::args().skip(1))
+    }
+
    
 }
 
+/// Caminho de um item na especificação, ex: `commands.calc` + `flags` + `numbers`
+fn spec_path(parent: &str, section: &str, name: &str) -> String {
+    if parent.is_empty() {
+        format!("{}.{}", section, name)
+    } else {
+        format!("{}.{}.{}", parent, section, name)
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct AppInfo {
     pub name: String,
@@ -1,4 +1,7 @@
-use crate::{App, Command, Flag, FlagType};
+use crate::cli::EXPLAIN_CONFIG_FLAG;
+use crate::flag::FlagValue;
+use crate::test_utils::TempDir;
+use crate::{App, Command, EnvSource, Flag, FlagType};
 
 #[test]
 fn test_create_app() {
@@ -47,3 +50,214 @@ fn test_help_requested() {
     assert!(result.is_ok());
     assert!(result.unwrap().help_requested);
 }
+
+#[test]
+fn test_resolve_nested_command_for_help() {
+    let app = App::new("app", "1.0.0").add_command(
+        Command::new("calc")
+            .description("Calculadora")
+            .add_subcommand(Command::new("add").description("Soma números")),
+    );
+
+    let parsed = app.parse(vec!["calc", "add", "--help"]).unwrap();
+    assert!(parsed.help_requested);
+
+    let command = app.resolve_command(&parsed.command_path());
+    assert_eq!(command.name, "add");
+    assert_eq!(command.description, "Soma números");
+}
+
+#[test]
+fn test_validation_subcommand_shadows_global_flag() {
+    let app = App::new("app", "1.0.0")
+        .add_global_flag(Flag::new("verbose", FlagType::Bool).short('v'))
+        .add_command(Command::new("hello").add_flag(Flag::new("version", FlagType::Bool).short('v')));
+    assert!(app.validate().is_err());
+
+    let app = App::new("app", "1.0.0")
+        .add_global_flag(Flag::new("verbose", FlagType::Bool).short('v'))
+        .add_command(Command::new("hello").add_flag(Flag::new("verbose", FlagType::String)));
+    assert!(app.validate().is_err());
+
+    let app = App::new("app", "1.0.0")
+        .add_global_flag(Flag::new("verbose", FlagType::Bool).short('v'))
+        .add_command(Command::new("hello").add_flag(Flag::new("name", FlagType::String).short('n')));
+    assert!(app.validate().is_ok());
+}
+
+#[test]
+fn test_env_source() {
+    let app = App::new("app", "1.0.0")
+        .env_source(EnvSource::from_pairs([("APP_NAME", "Rafael")]))
+        .add_command(
+            Command::new("hello")
+                .show_help_on_empty(false)
+                .add_flag(Flag::new("name", FlagType::String).env("APP_NAME").required(true)),
+        );
+
+    let parsed = app.parse(vec!["hello"]).unwrap();
+    assert_eq!(parsed.get_flag("name").unwrap().as_string().unwrap(), "Rafael");
+}
+
+#[test]
+fn test_config_file_and_flag_validation() {
+    let temp = TempDir::new("app-config");
+    let path = temp.path().join("app.toml");
+    std::fs::write(&path, "[hello]\nname = \"Config\"\n").unwrap();
+
+    let app = App::new("app", "1.0.0")
+        .config_file(&path)
+        .add_command(
+            Command::new("hello")
+                .show_help_on_empty(false)
+                .add_flag(Flag::new("name", FlagType::String).required(true)),
+        );
+
+    let parsed = app.parse(vec!["hello"]).unwrap();
+    assert_eq!(parsed.get_flag("name").unwrap().as_string().unwrap(), "Config");
+
+    let parsed = app.parse(vec!["hello", "--name", "Cli"]).unwrap();
+    assert_eq!(parsed.get_flag("name").unwrap().as_string().unwrap(), "Cli");
+
+    assert!(App::new("app", "1.0.0").config_flag("config").validate().is_err());
+}
+
+#[test]
+fn test_explain_config_flag() {
+    let app = App::new("app", "1.0.0")
+        .explain_config(true)
+        .add_command(Command::new("hello").add_flag(Flag::new("name", FlagType::String)));
+
+    assert!(app.root_command.get_flag(EXPLAIN_CONFIG_FLAG).unwrap().global);
+    assert!(app.validate().is_ok());
+
+    let parsed = app.run(vec!["hello", "--name", "Rafael", "--explain-config"]).unwrap();
+    assert!(parsed.get_flag(EXPLAIN_CONFIG_FLAG).unwrap().as_bool().unwrap());
+
+    let app = App::new("app", "1.0.0").explain_config(false);
+    assert!(app.root_command.get_flag(EXPLAIN_CONFIG_FLAG).is_none());
+}
+
+#[test]
+fn test_completion_command() {
+    let app = App::new("app", "1.0.0")
+        .add_command(Command::new("hello"))
+        .completion_command(true);
+
+    assert!(app.validate().is_ok());
+
+    let parsed = app.run(vec!["completion", "fish"]).unwrap();
+    assert_eq!(parsed.command_path(), vec!["completion"]);
+
+    match app.run(vec!["completion", "powershel"]).unwrap_err() {
+        crate::AppError::InvalidArgumentValue { arg, suggestions, .. } => {
+            assert_eq!(arg, "shell");
+            assert_eq!(suggestions, vec!["powershell"]);
+        }
+        err => panic!("Erro esperado: InvalidArgumentValue, erro recebido: {:?}", err),
+    }
+}
+
+#[test]
+fn test_dynamic_completion_command() {
+    let app = App::new("app", "1.0.0")
+        .add_global_flag(Flag::new("token", FlagType::String).required(true))
+        .add_command(Command::new("hello"))
+        .dynamic_completion(true);
+
+    assert!(app.validate().is_ok());
+    assert!(app.generate_completions(crate::Shell::Bash).contains("__complete"));
+
+    // Flags obrigatórias não impedem o autocompletar
+    let parsed = app.run(vec!["__complete", "0", "--", "he"]).unwrap();
+    assert_eq!(parsed.command_path(), vec!["__complete"]);
+
+    assert!(app.run(vec!["__complete", "x"]).is_err());
+}
+
+#[test]
+fn test_validation_rejects_unknown_relationship_flags() {
+    use crate::command::{ArgGroup, GroupKind};
+
+    let app = App::new("app", "1.0.0").add_command(
+        Command::new("export")
+            .add_flag(Flag::new("json", FlagType::Bool).conflicts_with("yaml")),
+    );
+    assert!(app.validate().is_err());
+
+    let app = App::new("app", "1.0.0").add_command(
+        Command::new("export")
+            .add_flag(Flag::new("json", FlagType::Bool))
+            .add_group(ArgGroup::new("formato", GroupKind::AtMostOne).flag("json").flag("table")),
+    );
+    assert!(app.validate().is_err());
+
+    let app = App::new("app", "1.0.0").add_command(
+        Command::new("export")
+            .add_flag(Flag::new("json", FlagType::Bool).conflicts_with("table"))
+            .add_flag(Flag::new("table", FlagType::Bool))
+            .add_group(ArgGroup::new("formato", GroupKind::AtMostOne).flag("json").flag("table")),
+    );
+    assert!(app.validate().is_ok());
+}
+
+#[test]
+fn test_validation_decrements_requires_count_flags() {
+    let app = App::new("app", "1.0.0")
+        .add_global_flag(Flag::new("verbose", FlagType::Bool))
+        .add_global_flag(Flag::new("quiet", FlagType::Count).decrements("verbose"));
+    assert!(app.validate().is_err());
+
+    let app = App::new("app", "1.0.0")
+        .add_global_flag(Flag::new("quiet", FlagType::Count).decrements("verbose"));
+    assert!(app.validate().is_err());
+
+    let app = App::new("app", "1.0.0")
+        .add_global_flag(Flag::new("verbose", FlagType::Count).short('v'))
+        .add_global_flag(Flag::new("quiet", FlagType::Count).short('q').decrements("verbose"));
+    assert!(app.validate().is_ok());
+}
+
+#[test]
+fn test_validation_negatable_flags() {
+    let app = App::new("app", "1.0.0")
+        .add_global_flag(Flag::new("name", FlagType::String).negatable(true));
+    assert!(app.validate().is_err());
+
+    let app = App::new("app", "1.0.0")
+        .add_global_flag(Flag::new("color", FlagType::Bool).negatable(true))
+        .add_global_flag(Flag::new("no-color", FlagType::Bool));
+    assert!(app.validate().is_err());
+
+    let app = App::new("app", "1.0.0")
+        .add_global_flag(Flag::new("color", FlagType::Bool).negatable(true));
+    assert!(app.validate().is_ok());
+}
+
+#[test]
+fn test_validation_optional_value_requires_valued_flag() {
+    let app = App::new("app", "1.0.0")
+        .add_global_flag(Flag::new("verbose", FlagType::Bool).optional_value(FlagValue::Bool(true)));
+    assert!(app.validate().is_err());
+
+    let app = App::new("app", "1.0.0").add_global_flag(
+        Flag::new("color", FlagType::String).optional_value(FlagValue::String("auto".to_string())),
+    );
+    assert!(app.validate().is_ok());
+}
+
+#[test]
+fn test_validation_num_values_and_delimiter() {
+    let app = App::new("app", "1.0.0")
+        .add_global_flag(Flag::new("name", FlagType::String).value_delimiter(Some(',')));
+    assert!(app.validate().is_err());
+
+    let app = App::new("app", "1.0.0")
+        .add_global_flag(Flag::new("point", FlagType::IntegerList).num_values(..=0));
+    assert!(app.validate().is_err());
+
+    let app = App::new("app", "1.0.0")
+        .add_global_flag(Flag::new("point", FlagType::IntegerList).num_values(2..=2))
+        .add_global_flag(Flag::new("tags", FlagType::StringList).value_delimiter(None));
+    assert!(app.validate().is_ok());
+}
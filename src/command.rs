@@ -1,21 +1,85 @@
 use std::collections::HashMap;
-use crate::Flag;
+use serde::Serialize;
+use crate::action::{Action, Context};
+use crate::{AppError, Flag};
+use crate::spec::sorted_map;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct Command {
+    /// Omitido na especificação, onde os comandos são indexados pelo nome
+    #[serde(skip)]
     pub name: String,
     pub description: String,
+    #[serde(serialize_with = "sorted_map")]
     pub flags: HashMap<String, Flag>,
+    #[serde(rename = "commands", serialize_with = "sorted_map")]
     pub subcommands: HashMap<String, Command>,
     pub positional_args: Vec<PositionalArg>,
-    pub show_help_on_empty: bool
+    pub show_help_on_empty: bool,
+    pub hidden: bool,
+    pub examples: Vec<Example>,
+    pub groups: Vec<ArgGroup>,
+    #[serde(skip)]
+    pub action: Option<Action>,
 }
 
-#[derive(Debug, Clone)]
+/// Exemplo de uso exibido na ajuda e na documentação gerada
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Example {
+    pub command: String,
+    pub description: String,
+}
+
+/// Quantas flags de um `ArgGroup` podem ser informadas
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GroupKind {
+    ExactlyOne,
+    AtLeastOne,
+    AtMostOne,
+}
+
+impl GroupKind {
+    /// Descrição exibida na ajuda, seguida da lista de flags
+    pub fn description(&self) -> &'static str {
+        match self {
+            GroupKind::ExactlyOne => "exatamente uma de",
+            GroupKind::AtLeastOne => "ao menos uma de",
+            GroupKind::AtMostOne => "no máximo uma de",
+        }
+    }
+}
+
+/// Grupo nomeado de flags do mesmo comando, ex: `--json` e `--table` em `formato`.
+/// Valores padrão não contam como informados
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ArgGroup {
+    pub name: String,
+    pub kind: GroupKind,
+    pub flags: Vec<String>,
+}
+
+impl ArgGroup {
+    pub fn new(name: impl Into<String>, kind: GroupKind) -> Self {
+        Self {
+            name: name.into(),
+            kind,
+            flags: Vec::new(),
+        }
+    }
+
+    pub fn flag(mut self, flag: impl Into<String>) -> Self {
+        self.flags.push(flag.into());
+        self
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
 pub struct PositionalArg {
     pub name: String,
     pub description: String,
-    pub required: bool
+    pub required: bool,
+    pub allow_hyphen_values: bool
 }
 
 impl PositionalArg {
@@ -23,7 +87,8 @@ impl PositionalArg {
         Self {
             name: name.into(),
             description: String::new(),
-            required: true
+            required: true,
+            allow_hyphen_values: false
         }
     }
 
@@ -36,6 +101,12 @@ impl PositionalArg {
         self.required = required;
         self
     }
+
+    /// Permite que o argumento comece com `-` (ex: números negativos)
+    pub fn allow_hyphen_values(mut self, allow_hyphen_values: bool) -> Self {
+        self.allow_hyphen_values = allow_hyphen_values;
+        self
+    }
 }
 
 impl Command {
@@ -46,7 +117,11 @@ impl Command {
             flags: HashMap::new(),
             subcommands: HashMap::new(),
             positional_args: Vec::new(),
-            show_help_on_empty: true
+            show_help_on_empty: true,
+            hidden: false,
+            examples: Vec::new(),
+            groups: Vec::new(),
+            action: None,
         }
     }
 
@@ -70,11 +145,40 @@ impl Command {
         self
     }
 
+    pub fn add_group(mut self, group: ArgGroup) -> Self {
+        self.groups.push(group);
+        self
+    }
+
     pub fn show_help_on_empty(mut self, show_help_on_empty: bool) -> Self {
         self.show_help_on_empty = show_help_on_empty;
         self
     }
 
+    /// Adiciona um exemplo de uso, ex: `("app calc add --numbers 1 2", "Soma 1 e 2")`
+    pub fn example(mut self, command: impl Into<String>, description: impl Into<String>) -> Self {
+        self.examples.push(Example {
+            command: command.into(),
+            description: description.into(),
+        });
+        self
+    }
+
+    /// Esconde o comando da ajuda e do autocompletar, sem deixar de aceitá-lo
+    pub fn hidden(mut self, hidden: bool) -> Self {
+        self.hidden = hidden;
+        self
+    }
+
+    /// Função executada por `App::dispatch` quando este é o comando escolhido
+    pub fn action<F>(mut self, action: F) -> Self
+    where
+        F: Fn(&Context) -> Result<(), AppError> + Send + Sync + 'static,
+    {
+        self.action = Some(Action::new(action));
+        self
+    }
+
     pub fn get_flag(&self, name: &str) -> Option<&Flag> {
         if let Some(flag) = self.flags.get(name) {
             return Some(flag);
@@ -96,6 +200,30 @@ impl Command {
         self.subcommands.get(name)
     }
 
+    /// Percorre um caminho de subcomandos, ex: `["calc", "add"]`
+    pub fn get_subcommand_by_path(&self, path: &[&str]) -> Option<&Command> {
+        path.iter()
+            .try_fold(self, |command, name| command.get_subcommand(name))
+    }
+
+    /// Flags globais definidas por este comando e pelos intermediários do caminho,
+    /// ou seja, as herdadas pelo subcomando em `path`
+    pub fn inherited_global_flags(&self, path: &[&str]) -> Vec<&Flag> {
+        let mut flags = Vec::new();
+        let mut current = self;
+
+        for name in path {
+            flags.extend(current.get_flags_sorted().into_iter().filter(|flag| flag.global));
+
+            match current.get_subcommand(name) {
+                Some(subcommand) => current = subcommand,
+                None => break,
+            }
+        }
+
+        flags
+    }
+
     pub fn get_flags_sorted(&self) -> Vec<&Flag> {
         let mut flags: Vec<&Flag> = self.flags.values().collect();
         flags.sort_by(|a, b| a.name.cmp(&b.name));
@@ -108,6 +236,14 @@ impl Command {
         subcommands
     }
 
+    /// Subcomandos ordenados por nome, sem os marcados com `hidden`
+    pub fn get_visible_subcommands(&self) -> Vec<&Command> {
+        self.get_subcommands_sorted()
+            .into_iter()
+            .filter(|subcommand| !subcommand.hidden)
+            .collect()
+    }
+
     pub fn has_subcommands(&self) -> bool {
         !self.subcommands.is_empty()
     }
@@ -0,0 +1,811 @@
+use crate::cli::COMPLETE_COMMAND;
+use crate::parser::CLIParser;
+use crate::{App, Command, Flag, FlagType};
+use std::fmt;
+use std::sync::Arc;
+
+/// Shells para os quais é possível gerar scripts de autocompletar
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+    Elvish,
+    PowerShell,
+}
+
+impl Shell {
+    pub const ALL: [Shell; 5] = [
+        Shell::Bash,
+        Shell::Zsh,
+        Shell::Fish,
+        Shell::Elvish,
+        Shell::PowerShell,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Shell::Bash => "bash",
+            Shell::Zsh => "zsh",
+            Shell::Fish => "fish",
+            Shell::Elvish => "elvish",
+            Shell::PowerShell => "powershell",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL
+            .into_iter()
+            .find(|shell| shell.name().eq_ignore_ascii_case(name))
+    }
+}
+
+/// Sugestão retornada pelo autocompletar dinâmico
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Candidate {
+    pub value: String,
+    pub description: String,
+}
+
+impl Candidate {
+    pub fn new(value: impl Into<String>) -> Self {
+        Self {
+            value: value.into(),
+            description: String::new(),
+        }
+    }
+
+    pub fn description(mut self, description: impl Into<String>) -> Self {
+        self.description = description.into();
+        self
+    }
+}
+
+/// Função registrada com `Flag::value_completer`, que recebe o valor digitado
+/// até o cursor e retorna as sugestões
+#[derive(Clone)]
+pub struct ValueCompleter(Arc<CompleterFn>);
+
+type CompleterFn = dyn Fn(&str) -> Vec<Candidate> + Send + Sync;
+
+impl ValueCompleter {
+    pub fn new<F>(completer: F) -> Self
+    where
+        F: Fn(&str) -> Vec<Candidate> + Send + Sync + 'static,
+    {
+        Self(Arc::new(completer))
+    }
+
+    pub fn complete(&self, prefix: &str) -> Vec<Candidate> {
+        (self.0)(prefix)
+    }
+}
+
+impl fmt::Debug for ValueCompleter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("ValueCompleter")
+    }
+}
+
+/// Sugestões para a palavra na posição `cursor_index` de `args` (sem o nome do
+/// programa). Se `cursor_index` for igual a `args.len()`, completa uma palavra nova.
+/// As palavras anteriores ao cursor são interpretadas pelo `CLIParser` para
+/// descobrir se o cursor está em um subcomando, no nome ou no valor de uma flag
+pub fn complete(app: &App, args: &[String], cursor_index: usize) -> Vec<Candidate> {
+    let cursor_index = cursor_index.min(args.len());
+    let current = args.get(cursor_index).map(String::as_str).unwrap_or_default();
+    let context = CLIParser::completion_context(&app.root_command, &args[..cursor_index]);
+
+    // Uma flag de lista aceita mais valores, mas `-` no início começa outra flag
+    if let Some(flag) = context.pending_flag
+        && !(flag.flag_type.is_list() && !flag.allow_hyphen_values && current.starts_with('-'))
+    {
+        return value_candidates(flag, current);
+    }
+
+    if context.after_terminator {
+        return Vec::new();
+    }
+
+    if let Some((name, value)) = current.strip_prefix("--").and_then(|rest| rest.split_once('=')) {
+        let Some(flag) = context.flags.iter().find(|flag| flag.name == name) else {
+            return Vec::new();
+        };
+
+        return value_candidates(flag, value)
+            .into_iter()
+            .map(|candidate| Candidate {
+                value: format!("--{}={}", name, candidate.value),
+                ..candidate
+            })
+            .collect();
+    }
+
+    let mut candidates = Vec::new();
+
+    if current.starts_with('-') {
+        let mut flags = context.flags.clone();
+        flags.sort_by(|a, b| a.name.cmp(&b.name));
+
+        for flag in &flags {
+            candidates.push(Candidate::new(format!("--{}", flag.name)).description(&flag.description));
+            if let Some(negated) = flag.negated_name() {
+                candidates.push(Candidate::new(format!("--{}", negated)).description(&flag.description));
+            }
+        }
+        for flag in &flags {
+            if let Some(short) = flag.short {
+                candidates.push(Candidate::new(format!("-{}", short)).description(&flag.description));
+            }
+        }
+    } else {
+        for subcommand in context.command.get_visible_subcommands() {
+            candidates.push(Candidate::new(&subcommand.name).description(&subcommand.description));
+        }
+    }
+
+    candidates.retain(|candidate| candidate.value.starts_with(current));
+    candidates
+}
+
+fn value_candidates(flag: &Flag, prefix: &str) -> Vec<Candidate> {
+    let mut candidates = match (&flag.value_completer, &flag.possible_values) {
+        (Some(completer), _) => completer.complete(prefix),
+        (None, Some(values)) => values.iter().map(Candidate::new).collect(),
+        (None, None) => Vec::new(),
+    };
+
+    candidates.retain(|candidate| candidate.value.starts_with(prefix));
+    candidates
+}
+
+/// Um comando da árvore com o caminho até ele e todas as flags aceitas nele
+/// (as próprias e as globais herdadas)
+struct CompletionNode<'a> {
+    path: Vec<&'a str>,
+    command: &'a Command,
+    flags: Vec<&'a Flag>,
+}
+
+impl CompletionNode<'_> {
+    fn key(&self, separator: &str) -> String {
+        self.path.join(separator)
+    }
+}
+
+fn collect_nodes<'a>(
+    command: &'a Command,
+    path: Vec<&'a str>,
+    inherited: Vec<&'a Flag>,
+    nodes: &mut Vec<CompletionNode<'a>>,
+) {
+    let mut flags = command.get_flags_sorted();
+    flags.extend(inherited.iter().copied());
+
+    let mut child_inherited = inherited;
+    child_inherited.extend(command.get_flags_sorted().into_iter().filter(|flag| flag.global));
+
+    nodes.push(CompletionNode {
+        path: path.clone(),
+        command,
+        flags,
+    });
+
+    for subcommand in command.get_visible_subcommands() {
+        let mut child_path = path.clone();
+        child_path.push(&subcommand.name);
+        collect_nodes(subcommand, child_path, child_inherited.clone(), nodes);
+    }
+}
+
+fn nodes(app: &App) -> Vec<CompletionNode<'_>> {
+    let mut nodes = Vec::new();
+    collect_nodes(&app.root_command, vec![app.name.as_str()], Vec::new(), &mut nodes);
+    nodes
+}
+
+fn takes_value(flag: &Flag) -> bool {
+    flag.flag_type.takes_value()
+}
+
+/// Flags que podem ser repetidas: listas e contadores
+fn is_list(flag: &Flag) -> bool {
+    flag.flag_type.is_list() || matches!(flag.flag_type, FlagType::Count)
+}
+
+/// Nome seguro para identificadores de função em shell
+fn identifier(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+fn single_quoted(text: &str) -> String {
+    format!("'{}'", text.replace('\'', "'\\''"))
+}
+
+/// Gera o script de autocompletar de `app` para `shell`
+pub fn generate(app: &App, shell: Shell) -> String {
+    match shell {
+        Shell::Bash => generate_bash(app),
+        Shell::Zsh => generate_zsh(app),
+        Shell::Fish => generate_fish(app),
+        Shell::Elvish => generate_elvish(app),
+        Shell::PowerShell => generate_powershell(app),
+    }
+}
+
+/// Gera um script curto que delega o autocompletar ao próprio binário, chamando
+/// `<app> __complete <índice> -- <palavras>` a cada tecla Tab. Cada linha da
+/// saída é uma sugestão no formato `valor<TAB>descrição`
+pub fn generate_dynamic(app: &App, shell: Shell) -> String {
+    let name = single_quoted(&app.name);
+    let function = format!("_{}", identifier(&app.name));
+
+    match shell {
+        Shell::Bash => format!(
+            "{function}() {{
+    local IFS=$'\\n'
+    COMPREPLY=($({name} {COMPLETE_COMMAND} \"$((COMP_CWORD - 1))\" -- \"${{COMP_WORDS[@]:1}}\" | cut -f1))
+}}
+
+complete -F {function} -o bashdefault -o default {bin}
+",
+            bin = app.name
+        ),
+        Shell::Zsh => format!(
+            "#compdef {bin}
+
+{function}() {{
+    local line
+    local -a candidates
+    for line in \"${{(@f)$({name} {COMPLETE_COMMAND} $((CURRENT - 2)) -- \"${{words[@]:1}}\")}}\"; do
+        [[ -n $line ]] && candidates+=(\"${{${{line%%$'\\t'*}}//:/\\\\:}}:${{line#*$'\\t'}}\")
+    done
+    _describe 'valores' candidates
+}}
+
+{function} \"$@\"
+",
+            bin = app.name
+        ),
+        Shell::Fish => format!(
+            "function __{ident}_complete
+    set -l tokens (commandline -opc)
+    set -e tokens[1]
+    {name} {COMPLETE_COMMAND} (count $tokens) -- $tokens (commandline -ct)
+end
+
+complete -c {bin} -f -a '(__{ident}_complete)'
+",
+            ident = identifier(&app.name),
+            bin = app.name
+        ),
+        Shell::Elvish => format!(
+            "use str;
+
+set edit:completion:arg-completer[{name}] = {{|@words|
+    var args = $words[1..]
+    e:{bin} {COMPLETE_COMMAND} (- (count $args) 1) -- $@args | each {{|line|
+        var parts = [(str:split \"\\t\" $line)]
+        edit:complex-candidate $parts[0] &display=(str:join ' ' $parts)
+    }}
+}}
+",
+            bin = app.name
+        ),
+        Shell::PowerShell => format!(
+            "Register-ArgumentCompleter -Native -CommandName {name} -ScriptBlock {{
+    param($wordToComplete, $commandAst, $cursorPosition)
+
+    $words = @($commandAst.CommandElements |
+        Select-Object -Skip 1 |
+        Where-Object {{ $_.Extent.StartOffset -lt $cursorPosition }} |
+        ForEach-Object {{ $_.ToString() }})
+    if (-not $wordToComplete) {{ $words += '' }}
+
+    & {name} {COMPLETE_COMMAND} ($words.Count - 1) -- @words | ForEach-Object {{
+        $value, $description = $_ -split \"`t\", 2
+        if (-not $description) {{ $description = $value }}
+        [System.Management.Automation.CompletionResult]::new($value, $value, 'ParameterValue', $description)
+    }}
+}}
+"
+        ),
+    }
+}
+
+fn generate_bash(app: &App) -> String {
+    let nodes = nodes(app);
+    let function = format!("_{}", identifier(&app.name));
+    let mut script = String::new();
+
+    script.push_str(&format!("{}() {{\n", function));
+    script.push_str("    local cur prev cmd i\n");
+    script.push_str("    COMPREPLY=()\n");
+    script.push_str("    cur=\"${COMP_WORDS[COMP_CWORD]}\"\n");
+    script.push_str("    prev=\"${COMP_WORDS[COMP_CWORD-1]}\"\n");
+    script.push_str(&format!("    cmd={}\n\n", single_quoted(&app.name)));
+
+    script.push_str("    for ((i = 1; i < COMP_CWORD; i++)); do\n");
+    script.push_str("        case \"${cmd}__${COMP_WORDS[i]}\" in\n");
+    for node in nodes.iter().skip(1) {
+        let key = node.key("__");
+        script.push_str(&format!("            {})\n", single_quoted(&key)));
+        script.push_str(&format!("                cmd={}\n", single_quoted(&key)));
+        script.push_str("                ;;\n");
+    }
+    script.push_str("        esac\n");
+    script.push_str("    done\n\n");
+
+    script.push_str("    case \"${cmd}\" in\n");
+    for node in &nodes {
+        script.push_str(&format!("        {})\n", single_quoted(&node.key("__"))));
+
+        let valued: Vec<&&Flag> = node.flags.iter().filter(|flag| takes_value(flag)).collect();
+        if !valued.is_empty() {
+            script.push_str("            case \"${prev}\" in\n");
+            for flag in valued {
+                let mut names = vec![format!("--{}", flag.name)];
+                if let Some(short) = flag.short {
+                    names.push(format!("-{}", short));
+                }
+
+                let reply = match &flag.possible_values {
+                    Some(values) => format!(
+                        "COMPREPLY=($(compgen -W {} -- \"${{cur}}\"))",
+                        single_quoted(&values.join(" "))
+                    ),
+                    None => "COMPREPLY=($(compgen -f -- \"${cur}\"))".to_string(),
+                };
+
+                script.push_str(&format!("                {})\n", names.join("|")));
+                script.push_str(&format!("                    {}\n", reply));
+                script.push_str("                    return 0\n");
+                script.push_str("                    ;;\n");
+            }
+            script.push_str("            esac\n");
+        }
+
+        let mut words: Vec<String> = Vec::new();
+        for flag in &node.flags {
+            words.push(format!("--{}", flag.name));
+            words.extend(flag.negated_name().map(|negated| format!("--{}", negated)));
+            if let Some(short) = flag.short {
+                words.push(format!("-{}", short));
+            }
+        }
+        words.extend(node.command.get_visible_subcommands().iter().map(|sub| sub.name.clone()));
+
+        script.push_str(&format!(
+            "            COMPREPLY=($(compgen -W {} -- \"${{cur}}\"))\n",
+            single_quoted(&words.join(" "))
+        ));
+
+        if node.command.has_positional_args() {
+            script.push_str("            COMPREPLY+=($(compgen -f -- \"${cur}\"))\n");
+        }
+
+        script.push_str("            ;;\n");
+    }
+    script.push_str("    esac\n");
+    script.push_str("}\n\n");
+
+    script.push_str(&format!(
+        "complete -F {} -o bashdefault -o default {}\n",
+        function, app.name
+    ));
+
+    script
+}
+
+/// Escapa texto para uso dentro de `[...]` em especificações do `_arguments` do zsh
+fn zsh_description(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace('\'', "'\\''")
+        .replace('[', "\\[")
+        .replace(']', "\\]")
+        .replace(':', "\\:")
+}
+
+/// Escapa texto para itens do `_describe` do zsh, onde `:` separa nome e descrição
+fn zsh_describe_item(text: &str) -> String {
+    text.replace('\'', "'\\''").replace(':', "\\:")
+}
+
+fn zsh_flag_spec(flag: &Flag) -> String {
+    let repeat = if is_list(flag) { "*" } else { "" };
+    let description = zsh_description(&flag.description);
+
+    let action = if takes_value(flag) {
+        let values = match &flag.possible_values {
+            Some(values) => format!("({})", values.join(" ")),
+            None => "_default".to_string(),
+        };
+        format!(":{}:{}", flag.name, values)
+    } else {
+        String::new()
+    };
+
+    match flag.short {
+        Some(short) if takes_value(flag) => format!(
+            "'{repeat}(-{short} --{name})'{{-{short}+,--{name}=}}'[{description}]{action}'",
+            name = flag.name
+        ),
+        Some(short) => format!(
+            "'{repeat}(-{short} --{name})'{{-{short},--{name}}}'[{description}]'",
+            name = flag.name
+        ),
+        None if takes_value(flag) => {
+            format!("'{repeat}--{name}=[{description}]{action}'", name = flag.name)
+        }
+        None => format!("'{repeat}--{name}[{description}]'", name = flag.name),
+    }
+}
+
+fn generate_zsh(app: &App) -> String {
+    let nodes = nodes(app);
+    let mut script = format!("#compdef {}\n", app.name);
+
+    for node in &nodes {
+        let function = format!("_{}", identifier(&node.key("__")));
+        script.push_str(&format!("\n{}() {{\n", function));
+        script.push_str("    local context curcontext=\"$curcontext\" state line\n");
+        script.push_str("    _arguments -C \\\n");
+
+        for flag in &node.flags {
+            script.push_str(&format!("        {} \\\n", zsh_flag_spec(flag)));
+            if let Some(negated) = flag.negated_name() {
+                script.push_str(&format!(
+                    "        '--{}[{}]' \\\n",
+                    negated,
+                    zsh_description(&flag.description)
+                ));
+            }
+        }
+
+        if !node.command.get_visible_subcommands().is_empty() {
+            script.push_str("        '1: :->commands' \\\n");
+            script.push_str("        '*::arg:->args'\n");
+        } else {
+            for (index, arg) in node.command.positional_args.iter().enumerate() {
+                let optional = if arg.required { "" } else { ":" };
+                script.push_str(&format!(
+                    "        '{}:{}{}:_default' \\\n",
+                    index + 1,
+                    optional,
+                    zsh_description(&arg.name)
+                ));
+            }
+            script.push_str("        && return 0\n");
+            script.push_str("}\n");
+            continue;
+        }
+
+        script.push_str("\n    case $state in\n");
+        script.push_str("        commands)\n");
+        script.push_str("            local -a commands\n");
+        script.push_str("            commands=(\n");
+        for subcommand in node.command.get_visible_subcommands() {
+            script.push_str(&format!(
+                "                '{}:{}'\n",
+                zsh_describe_item(&subcommand.name),
+                zsh_describe_item(&subcommand.description)
+            ));
+        }
+        script.push_str("            )\n");
+        script.push_str("            _describe -t commands 'comandos' commands\n");
+        script.push_str("            ;;\n");
+        script.push_str("        args)\n");
+        script.push_str("            case $line[1] in\n");
+        for subcommand in node.command.get_visible_subcommands() {
+            let mut child_path = node.path.clone();
+            child_path.push(&subcommand.name);
+            script.push_str(&format!(
+                "                {}) _{} ;;\n",
+                subcommand.name,
+                identifier(&child_path.join("__"))
+            ));
+        }
+        script.push_str("            esac\n");
+        script.push_str("            ;;\n");
+        script.push_str("    esac\n");
+        script.push_str("}\n");
+    }
+
+    script.push_str(&format!("\n_{} \"$@\"\n", identifier(&app.name)));
+    script
+}
+
+/// Condição do fish que identifica o comando em `node.path`
+fn fish_condition(node: &CompletionNode) -> String {
+    let path = &node.path[1..];
+    if path.is_empty() {
+        return "__fish_use_subcommand".to_string();
+    }
+
+    let mut conditions: Vec<String> = path
+        .iter()
+        .map(|name| format!("__fish_seen_subcommand_from {}", name))
+        .collect();
+
+    if !node.command.get_visible_subcommands().is_empty() {
+        let children: Vec<&str> = node
+            .command
+            .get_visible_subcommands()
+            .iter()
+            .map(|sub| sub.name.as_str())
+            .collect();
+        conditions.push(format!("not __fish_seen_subcommand_from {}", children.join(" ")));
+    }
+
+    conditions.join("; and ")
+}
+
+fn generate_fish(app: &App) -> String {
+    let nodes = nodes(app);
+    let mut script = format!("complete -c {} -f\n", app.name);
+
+    for node in &nodes {
+        let condition = fish_condition(node);
+        script.push('\n');
+
+        for subcommand in node.command.get_visible_subcommands() {
+            script.push_str(&format!(
+                "complete -c {} -n {} -a {} -d {}\n",
+                app.name,
+                single_quoted(&condition),
+                single_quoted(&subcommand.name),
+                single_quoted(&subcommand.description)
+            ));
+        }
+
+        for flag in node.command.get_flags_sorted() {
+            // Flags globais são completadas em qualquer nível, sem condição
+            let condition_part = if flag.global {
+                String::new()
+            } else {
+                format!(" -n {}", single_quoted(&condition))
+            };
+
+            let mut line = format!("complete -c {}{}", app.name, condition_part);
+            if let Some(short) = flag.short {
+                line.push_str(&format!(" -s {}", short));
+            }
+            line.push_str(&format!(" -l {}", flag.name));
+
+            if takes_value(flag) {
+                line.push_str(" -r");
+                match &flag.possible_values {
+                    Some(values) => line.push_str(&format!(" -a {}", single_quoted(&values.join(" ")))),
+                    None => line.push_str(" -F"),
+                }
+            }
+
+            line.push_str(&format!(" -d {}", single_quoted(&flag.description)));
+            script.push_str(&line);
+            script.push('\n');
+
+            if let Some(negated) = flag.negated_name() {
+                script.push_str(&format!(
+                    "complete -c {}{} -l {} -d {}\n",
+                    app.name,
+                    condition_part,
+                    negated,
+                    single_quoted(&flag.description)
+                ));
+            }
+        }
+
+        if node.command.has_positional_args() {
+            script.push_str(&format!(
+                "complete -c {} -n {} -F\n",
+                app.name,
+                single_quoted(&condition)
+            ));
+        }
+    }
+
+    script
+}
+
+fn generate_elvish(app: &App) -> String {
+    let nodes = nodes(app);
+    let mut script = String::new();
+
+    script.push_str("use builtin;\n");
+    script.push_str("use str;\n\n");
+    script.push_str(&format!(
+        "set edit:completion:arg-completer[{}] = {{|@words|\n",
+        single_quoted(&app.name)
+    ));
+
+    script.push_str("    var values = [\n");
+    for node in &nodes {
+        for flag in node.flags.iter().filter(|flag| takes_value(flag)) {
+            let Some(values) = &flag.possible_values else {
+                continue;
+            };
+            let list: Vec<String> = values.iter().map(|value| single_quoted(value)).collect();
+
+            let mut names = vec![format!("--{}", flag.name)];
+            if let Some(short) = flag.short {
+                names.push(format!("-{}", short));
+            }
+            for name in names {
+                script.push_str(&format!(
+                    "        &{}= [{}]\n",
+                    single_quoted(&format!("{};{}", node.key(";"), name)),
+                    list.join(" ")
+                ));
+            }
+        }
+    }
+    script.push_str("    ]\n\n");
+
+    script.push_str("    var completions = [\n");
+    for node in &nodes {
+        script.push_str(&format!("        &{}= {{\n", single_quoted(&node.key(";"))));
+        for flag in &node.flags {
+            script.push_str(&format!(
+                "            edit:complex-candidate {} &display={}\n",
+                single_quoted(&format!("--{}", flag.name)),
+                single_quoted(&format!("--{} ({})", flag.name, flag.description))
+            ));
+            if let Some(negated) = flag.negated_name() {
+                script.push_str(&format!(
+                    "            edit:complex-candidate {} &display={}\n",
+                    single_quoted(&format!("--{}", negated)),
+                    single_quoted(&format!("--{} ({})", negated, flag.description))
+                ));
+            }
+            if let Some(short) = flag.short {
+                script.push_str(&format!(
+                    "            edit:complex-candidate {} &display={}\n",
+                    single_quoted(&format!("-{}", short)),
+                    single_quoted(&format!("-{} ({})", short, flag.description))
+                ));
+            }
+        }
+        for subcommand in node.command.get_visible_subcommands() {
+            script.push_str(&format!(
+                "            edit:complex-candidate {} &display={}\n",
+                single_quoted(&subcommand.name),
+                single_quoted(&format!("{} ({})", subcommand.name, subcommand.description))
+            ));
+        }
+        script.push_str("        }\n");
+    }
+    script.push_str("    ]\n\n");
+
+    script.push_str(&format!("    var command = {}\n", single_quoted(&app.name)));
+    script.push_str("    for word $words[1..-1] {\n");
+    script.push_str("        if (has-key $completions $command';'$word) {\n");
+    script.push_str("            set command = $command';'$word\n");
+    script.push_str("        }\n");
+    script.push_str("    }\n\n");
+    script.push_str("    if (> (count $words) 2) {\n");
+    script.push_str("        var key = $command';'$words[-2]\n");
+    script.push_str("        if (has-key $values $key) {\n");
+    script.push_str("            all $values[$key]\n");
+    script.push_str("            return\n");
+    script.push_str("        }\n");
+    script.push_str("    }\n\n");
+    script.push_str("    $completions[$command]\n");
+    script.push_str("}\n");
+
+    script
+}
+
+fn generate_powershell(app: &App) -> String {
+    let nodes = nodes(app);
+    let mut script = String::new();
+
+    script.push_str("using namespace System.Management.Automation\n");
+    script.push_str("using namespace System.Management.Automation.Language\n\n");
+    script.push_str(&format!(
+        "Register-ArgumentCompleter -Native -CommandName {} -ScriptBlock {{\n",
+        single_quoted(&app.name)
+    ));
+    script.push_str("    param($wordToComplete, $commandAst, $cursorPosition)\n\n");
+
+    let known: Vec<String> = nodes.iter().map(|node| single_quoted(&node.key(";"))).collect();
+    script.push_str(&format!("    $known = @({})\n", known.join(", ")));
+
+    script.push_str("    $values = @{\n");
+    for node in &nodes {
+        for flag in node.flags.iter().filter(|flag| takes_value(flag)) {
+            let Some(values) = &flag.possible_values else {
+                continue;
+            };
+            let list: Vec<String> = values.iter().map(|value| single_quoted(value)).collect();
+
+            let mut names = vec![format!("--{}", flag.name)];
+            if let Some(short) = flag.short {
+                names.push(format!("-{}", short));
+            }
+            for name in names {
+                script.push_str(&format!(
+                    "        {} = @({})\n",
+                    single_quoted(&format!("{};{}", node.key(";"), name)),
+                    list.join(", ")
+                ));
+            }
+        }
+    }
+    script.push_str("    }\n\n");
+
+    script.push_str(&format!("    $command = {}\n", single_quoted(&app.name)));
+    script.push_str("    $elements = $commandAst.CommandElements\n");
+    script.push_str("    for ($i = 1; $i -lt $elements.Count; $i++) {\n");
+    script.push_str("        $value = $elements[$i].ToString()\n");
+    script.push_str("        if ($value -eq $wordToComplete) { break }\n");
+    script.push_str("        if ($known -contains \"$command;$value\") { $command = \"$command;$value\" }\n");
+    script.push_str("    }\n\n");
+
+    script.push_str("    $previous = if ($wordToComplete) { $elements[-2] } else { $elements[-1] }\n");
+    script.push_str("    $key = \"$command;$previous\"\n");
+    script.push_str("    if ($values.ContainsKey($key)) {\n");
+    script.push_str("        return $values[$key] | Where-Object { $_ -like \"$wordToComplete*\" } | ForEach-Object {\n");
+    script.push_str("            [CompletionResult]::new($_, $_, [CompletionResultType]::ParameterValue, $_)\n");
+    script.push_str("        }\n");
+    script.push_str("    }\n\n");
+
+    script.push_str("    $completions = @(switch ($command) {\n");
+    for node in &nodes {
+        script.push_str(&format!("        {} {{\n", single_quoted(&node.key(";"))));
+        for flag in &node.flags {
+            let tooltip = if flag.description.is_empty() {
+                flag.name.clone()
+            } else {
+                flag.description.clone()
+            };
+            script.push_str(&format!(
+                "            [CompletionResult]::new({}, {}, [CompletionResultType]::ParameterName, {})\n",
+                single_quoted(&format!("--{}", flag.name)),
+                single_quoted(&flag.name),
+                single_quoted(&tooltip).replace("'\\''", "''")
+            ));
+            if let Some(negated) = flag.negated_name() {
+                script.push_str(&format!(
+                    "            [CompletionResult]::new({}, {}, [CompletionResultType]::ParameterName, {})\n",
+                    single_quoted(&format!("--{}", negated)),
+                    single_quoted(&negated),
+                    single_quoted(&tooltip).replace("'\\''", "''")
+                ));
+            }
+            if let Some(short) = flag.short {
+                script.push_str(&format!(
+                    "            [CompletionResult]::new({}, {}, [CompletionResultType]::ParameterName, {})\n",
+                    single_quoted(&format!("-{}", short)),
+                    single_quoted(&short.to_string()),
+                    single_quoted(&tooltip).replace("'\\''", "''")
+                ));
+            }
+        }
+        for subcommand in node.command.get_visible_subcommands() {
+            let tooltip = if subcommand.description.is_empty() {
+                subcommand.name.clone()
+            } else {
+                subcommand.description.clone()
+            };
+            script.push_str(&format!(
+                "            [CompletionResult]::new({name}, {name}, [CompletionResultType]::ParameterValue, {})\n",
+                single_quoted(&tooltip).replace("'\\''", "''"),
+                name = single_quoted(&subcommand.name)
+            ));
+        }
+        script.push_str("            break\n");
+        script.push_str("        }\n");
+    }
+    script.push_str("    })\n\n");
+
+    script.push_str("    $completions.Where{ $_.CompletionText -like \"$wordToComplete*\" } |\n");
+    script.push_str("        Sort-Object -Property ListItemText\n");
+    script.push_str("}\n");
+
+    script
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,251 @@
+use super::*;
+use crate::command::PositionalArg;
+
+fn test_app() -> App {
+    App::new("app", "1.0.0")
+        .add_global_flag(Flag::new("verbose", FlagType::Bool).short('v').description("Modo verboso"))
+        .add_command(
+            Command::new("hello")
+                .description("Diz olá")
+                .add_flag(
+                    Flag::new("format", FlagType::String)
+                        .short('f')
+                        .possible_values(vec!["json".to_string(), "text".to_string()]),
+                )
+                .add_positional_arg(PositionalArg::new("name")),
+        )
+        .add_command(
+            Command::new("calc")
+                .description("Calculadora")
+                .add_subcommand(
+                    Command::new("add")
+                        .description("Soma números")
+                        .add_flag(Flag::new("numbers", FlagType::IntegerList)),
+                ),
+        )
+}
+
+#[test]
+fn test_shell_from_name() {
+    assert_eq!(Shell::from_name("bash"), Some(Shell::Bash));
+    assert_eq!(Shell::from_name("PowerShell"), Some(Shell::PowerShell));
+    assert_eq!(Shell::from_name("tcsh"), None);
+
+    for shell in Shell::ALL {
+        assert_eq!(Shell::from_name(shell.name()), Some(shell));
+    }
+}
+
+#[test]
+fn test_nodes_walk_whole_tree() {
+    let app = test_app();
+    let nodes = nodes(&app);
+
+    let paths: Vec<String> = nodes.iter().map(|node| node.key(" ")).collect();
+    assert_eq!(paths, vec!["app", "app calc", "app calc add", "app hello"]);
+
+    // Flags globais aparecem em todos os níveis
+    for node in &nodes {
+        assert!(node.flags.iter().any(|flag| flag.name == "verbose"));
+    }
+}
+
+#[test]
+fn test_bash_script() {
+    let script = generate(&test_app(), Shell::Bash);
+
+    assert!(script.contains("complete -F _app"));
+    assert!(script.contains("'app__calc__add')"));
+    assert!(script.contains("--format|-f)"));
+    assert!(script.contains("compgen -W 'json text'"));
+    assert!(script.contains("'--numbers --verbose -v'"));
+}
+
+#[test]
+fn test_zsh_script() {
+    let script = generate(&test_app(), Shell::Zsh);
+
+    assert!(script.starts_with("#compdef app\n"));
+    assert!(script.contains("_app__calc__add() {"));
+    assert!(script.contains("'(-v --verbose)'{-v,--verbose}'[Modo verboso]'"));
+    assert!(script.contains("{-f+,--format=}'[]:format:(json text)'"));
+    assert!(script.contains("'*--numbers=[]:numbers:_default'"));
+    assert!(script.contains("'1:name:_default'"));
+    assert!(script.contains("'calc:Calculadora'"));
+}
+
+#[test]
+fn test_fish_script() {
+    let script = generate(&test_app(), Shell::Fish);
+
+    assert!(script.contains("complete -c app -s v -l verbose -d 'Modo verboso'"));
+    assert!(script.contains(
+        "complete -c app -n '__fish_use_subcommand' -a 'calc' -d 'Calculadora'"
+    ));
+    assert!(script.contains(
+        "-n '__fish_seen_subcommand_from calc; and not __fish_seen_subcommand_from add' -a 'add'"
+    ));
+    assert!(script.contains("-s f -l format -r -a 'json text'"));
+}
+
+#[test]
+fn test_elvish_and_powershell_scripts() {
+    let elvish = generate(&test_app(), Shell::Elvish);
+    assert!(elvish.contains("set edit:completion:arg-completer['app']"));
+    assert!(elvish.contains("&'app;calc;add'= {"));
+    assert!(elvish.contains("&'app;hello;--format'= ['json' 'text']"));
+
+    let powershell = generate(&test_app(), Shell::PowerShell);
+    assert!(powershell.contains("Register-ArgumentCompleter -Native -CommandName 'app'"));
+    assert!(powershell.contains("$known = @('app', 'app;calc', 'app;calc;add', 'app;hello')"));
+    assert!(powershell.contains("'app;hello;-f' = @('json', 'text')"));
+}
+
+#[test]
+fn test_quotes_are_escaped() {
+    let app = App::new("app", "1.0.0")
+        .add_command(Command::new("go").description("Vai d'aqui"));
+
+    assert!(generate(&app, Shell::Fish).contains("-d 'Vai d'\\''aqui'"));
+    assert!(generate(&app, Shell::PowerShell).contains("'Vai d''aqui'"));
+}
+
+fn args(values: &[&str]) -> Vec<String> {
+    values.iter().map(|value| value.to_string()).collect()
+}
+
+fn values(candidates: Vec<Candidate>) -> Vec<String> {
+    candidates.into_iter().map(|candidate| candidate.value).collect()
+}
+
+#[test]
+fn test_complete_subcommands() {
+    let app = test_app();
+
+    assert_eq!(values(complete(&app, &[], 0)), vec!["calc", "hello"]);
+    assert_eq!(values(complete(&app, &args(&["c"]), 0)), vec!["calc"]);
+    assert_eq!(values(complete(&app, &args(&["-v", "calc", ""]), 2)), vec!["add"]);
+
+    let candidates = complete(&app, &args(&["calc"]), 1);
+    assert_eq!(candidates, vec![Candidate::new("add").description("Soma números")]);
+}
+
+#[test]
+fn test_complete_flag_names() {
+    let app = test_app();
+
+    assert_eq!(
+        values(complete(&app, &args(&["hello", "-"]), 1)),
+        vec!["--format", "--verbose", "-f", "-v"]
+    );
+    assert_eq!(values(complete(&app, &args(&["hello", "--v"]), 1)), vec!["--verbose"]);
+}
+
+#[test]
+fn test_complete_flag_values() {
+    let app = test_app();
+
+    assert_eq!(values(complete(&app, &args(&["hello", "--format"]), 2)), vec!["json", "text"]);
+    assert_eq!(values(complete(&app, &args(&["hello", "-vf", "j"]), 2)), vec!["json"]);
+    assert_eq!(values(complete(&app, &args(&["hello", "--format=t"]), 1)), vec!["--format=text"]);
+
+    // Depois do valor, volta a completar subcomandos/flags normalmente
+    assert!(complete(&app, &args(&["hello", "--format", "json", ""]), 3).is_empty());
+    assert!(complete(&app, &args(&["hello", "--", "-"]), 2).is_empty());
+}
+
+#[test]
+fn test_list_flag_stays_pending() {
+    let colors = || vec!["blue".to_string(), "red".to_string()];
+    let app = App::new("app", "1.0.0")
+        .add_global_flag(Flag::new("verbose", FlagType::Bool).short('v'))
+        .add_command(
+            Command::new("paint")
+                .add_flag(Flag::new("colors", FlagType::StringList).possible_values(colors()))
+                .add_flag(
+                    Flag::new("base", FlagType::StringList)
+                        .possible_values(colors())
+                        .num_values(1..=1),
+                )
+                .add_subcommand(Command::new("wall")),
+        );
+
+    assert_eq!(values(complete(&app, &args(&["paint", "--colors", "red", ""]), 3)), vec!["blue", "red"]);
+    assert_eq!(values(complete(&app, &args(&["paint", "--colors", "red", "blue", "r"]), 4)), vec!["red"]);
+
+    // A próxima flag ou subcomando encerra a lista, como no parsing
+    assert_eq!(
+        values(complete(&app, &args(&["paint", "--colors", "red", "--v"]), 3)),
+        vec!["--verbose"]
+    );
+    assert_eq!(values(complete(&app, &args(&["paint", "--colors", "red", "-v", ""]), 4)), vec!["wall"]);
+    assert!(complete(&app, &args(&["paint", "--colors", "red", "wall", ""]), 4).is_empty());
+
+    // Com num_values, para ao atingir o máximo
+    assert_eq!(values(complete(&app, &args(&["paint", "--base", "red", ""]), 3)), vec!["wall"]);
+}
+
+#[test]
+fn test_list_flag_leaves_required_positionals() {
+    let app = App::new("app", "1.0.0").add_command(
+        Command::new("paint")
+            .add_flag(
+                Flag::new("colors", FlagType::StringList)
+                    .possible_values(vec!["blue".to_string(), "red".to_string()]),
+            )
+            .add_positional_arg(PositionalArg::new("file"))
+            .add_subcommand(Command::new("wall")),
+    );
+
+    // `blue` fica para o argumento posicional obrigatório, como no parsing
+    assert_eq!(values(complete(&app, &args(&["paint", "--colors", "red", ""]), 3)), vec!["blue", "red"]);
+    assert_eq!(values(complete(&app, &args(&["paint", "--colors", "red", "blue", ""]), 4)), vec!["wall"]);
+}
+
+#[test]
+fn test_value_completer() {
+    let app = App::new("git", "1.0.0").add_command(
+        Command::new("checkout").add_flag(Flag::new("branch", FlagType::String).short('b').value_completer(
+            |prefix| {
+                ["main", "master", "develop"]
+                    .into_iter()
+                    .filter(|branch| branch.starts_with(prefix))
+                    .map(Candidate::new)
+                    .collect()
+            },
+        )),
+    );
+
+    assert_eq!(values(complete(&app, &args(&["checkout", "-b", "ma"]), 2)), vec!["main", "master"]);
+}
+
+#[test]
+fn test_hidden_commands_are_not_completed() {
+    let app = test_app().add_command(Command::new("internal").hidden(true));
+
+    assert_eq!(values(complete(&app, &[], 0)), vec!["calc", "hello"]);
+    assert!(!generate(&app, Shell::Bash).contains("internal"));
+}
+
+#[test]
+fn test_dynamic_scripts_call_back_into_binary() {
+    let app = test_app();
+
+    for shell in Shell::ALL {
+        assert!(generate_dynamic(&app, shell).contains("__complete"));
+    }
+    assert!(generate_dynamic(&app, Shell::Fish).contains("complete -c app -f -a '(__app_complete)'"));
+}
+
+#[test]
+fn test_negatable_flags_are_completed() {
+    let app = App::new("app", "1.0.0")
+        .add_global_flag(Flag::new("color", FlagType::Bool).negatable(true).description("Cores"));
+
+    assert_eq!(values(complete(&app, &args(&["--"]), 0)), vec!["--color", "--no-color"]);
+    assert!(generate(&app, Shell::Bash).contains("'--color --no-color'"));
+    assert!(generate(&app, Shell::Zsh).contains("'--no-color[Cores]'"));
+    assert!(generate(&app, Shell::Fish).contains("complete -c app -l no-color -d 'Cores'"));
+    assert!(generate(&app, Shell::Elvish).contains("edit:complex-candidate '--no-color'"));
+    assert!(generate(&app, Shell::PowerShell).contains("[CompletionResult]::new('--no-color', 'no-color'"));
+}
@@ -0,0 +1,308 @@
+use crate::env::EnvSource;
+use crate::flag::FlagValue;
+use crate::parser::CLIParser;
+use crate::{AppError, Command, Flag, FlagType};
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+
+/// Origem de um arquivo de configuração registrado no `App`
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfigSource {
+    /// Arquivo em um caminho fixo, ex: `.minha-app.toml` no diretório do projeto.
+    /// Ignorado se não existir
+    File(PathBuf),
+    /// Arquivo dentro do diretório de configuração do usuário
+    /// (`$XDG_CONFIG_HOME/<app>/` ou `~/.config/<app>/`). Ignorado se não existir
+    UserConfig(String),
+    /// Caminho informado pela flag com este nome (ex: `--config caminho`).
+    /// O arquivo precisa existir
+    Flag(String),
+}
+
+/// Formatos de arquivo suportados, escolhidos pela extensão
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConfigFormat {
+    Toml,
+    Json,
+    Yaml,
+}
+
+impl ConfigFormat {
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "toml" => Some(ConfigFormat::Toml),
+            "json" => Some(ConfigFormat::Json),
+            "yaml" | "yml" => Some(ConfigFormat::Yaml),
+            _ => None,
+        }
+    }
+}
+
+/// Valores de um arquivo de configuração. Chaves no nível superior são flags do
+/// comando raiz e tabelas são subcomandos, ex:
+///
+/// ```toml
+/// timeout = 30
+///
+/// [calc.add]
+/// numbers = [1, 2, 3]
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConfigLayer {
+    pub path: PathBuf,
+    pub values: Value,
+}
+
+impl ConfigLayer {
+    pub fn from_str(
+        path: impl Into<PathBuf>,
+        contents: &str,
+        format: ConfigFormat,
+    ) -> Result<Self, AppError> {
+        let path = path.into();
+
+        let values: Result<Value, String> = match format {
+            ConfigFormat::Toml => toml::from_str(contents).map_err(|e| e.to_string()),
+            ConfigFormat::Json => serde_json::from_str(contents).map_err(|e| e.to_string()),
+            ConfigFormat::Yaml => serde_yaml::from_str(contents).map_err(|e| e.to_string()),
+        };
+
+        let values = values.map_err(|message| AppError::ConfigFileError {
+            path: path.display().to_string(),
+            message,
+        })?;
+
+        if !values.is_object() && !values.is_null() {
+            return Err(AppError::ConfigFileError {
+                path: path.display().to_string(),
+                message: "o conteúdo deve ser uma tabela de chaves e valores".to_string(),
+            });
+        }
+
+        Ok(Self { path, values })
+    }
+
+    pub fn load(path: impl Into<PathBuf>) -> Result<Self, AppError> {
+        let path = path.into();
+
+        let format = ConfigFormat::from_path(&path).ok_or_else(|| AppError::ConfigFileError {
+            path: path.display().to_string(),
+            message: "extensão não suportada, use .toml, .json, .yaml ou .yml".to_string(),
+        })?;
+
+        let contents = std::fs::read_to_string(&path).map_err(|e| AppError::ConfigFileError {
+            path: path.display().to_string(),
+            message: e.to_string(),
+        })?;
+
+        Self::from_str(path, &contents, format)
+    }
+
+    fn get(&self, command_path: &[&str], flag_name: &str) -> Option<&Value> {
+        command_path
+            .iter()
+            .try_fold(&self.values, |table, name| table.get(name))?
+            .get(flag_name)
+    }
+
+    /// Verifica se todas as chaves correspondem a flags ou subcomandos de `command`.
+    /// Flags globais de comandos acima (`inherited`) também são aceitas
+    fn validate_keys(
+        &self,
+        command: &Command,
+        inherited: &[&str],
+        prefix: &str,
+        table: &Value,
+    ) -> Result<(), AppError> {
+        let Some(table) = table.as_object() else {
+            return Ok(());
+        };
+
+        let mut globals = inherited.to_vec();
+        globals.extend(command.flags.values().filter(|flag| flag.global).map(|flag| flag.name.as_str()));
+
+        for (key, value) in table {
+            let full_key = if prefix.is_empty() {
+                key.clone()
+            } else {
+                format!("{}.{}", prefix, key)
+            };
+
+            if let Some(subcommand) = command.get_subcommand(key) {
+                if !value.is_object() {
+                    return Err(self.invalid_value(&full_key, "esperado uma tabela com as flags do subcomando"));
+                }
+                self.validate_keys(subcommand, &globals, &full_key, value)?;
+            } else if !command.flags.contains_key(key) && !inherited.contains(&key.as_str()) {
+                return Err(self.invalid_value(&full_key, "chave não corresponde a nenhuma flag ou subcomando"));
+            }
+        }
+
+        Ok(())
+    }
+
+    fn invalid_value(&self, key: &str, message: impl Into<String>) -> AppError {
+        AppError::InvalidConfigValue {
+            path: self.path.display().to_string(),
+            key: key.to_string(),
+            message: message.into(),
+        }
+    }
+}
+
+/// Camadas de configuração em ordem crescente de precedência
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ConfigValues {
+    pub layers: Vec<ConfigLayer>,
+}
+
+impl ConfigValues {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adiciona uma camada com precedência maior que as anteriores
+    pub fn push(&mut self, layer: ConfigLayer) {
+        self.layers.push(layer);
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.layers.is_empty()
+    }
+
+    /// Carrega as fontes registradas em ordem de precedência: diretório do usuário,
+    /// arquivos fixos (na ordem em que foram adicionados) e por último o arquivo
+    /// indicado pela flag
+    pub fn load(
+        app_name: &str,
+        sources: &[ConfigSource],
+        root: &Command,
+        args: &[String],
+        env: &EnvSource,
+    ) -> Result<Self, AppError> {
+        let mut config = Self::new();
+
+        for source in sources {
+            if let ConfigSource::UserConfig(file_name) = source
+                && let Some(dir) = user_config_dir(env)
+            {
+                config.push_if_exists(dir.join(app_name).join(file_name))?;
+            }
+        }
+
+        for source in sources {
+            if let ConfigSource::File(path) = source {
+                config.push_if_exists(path.clone())?;
+            }
+        }
+
+        for source in sources {
+            if let ConfigSource::Flag(flag_name) = source
+                && let Some(path) = CLIParser::root_flag_value(root, flag_name, args)
+            {
+                config.push(ConfigLayer::load(path)?);
+            }
+        }
+
+        config.validate_keys(root)?;
+        Ok(config)
+    }
+
+    fn push_if_exists(&mut self, path: PathBuf) -> Result<(), AppError> {
+        if path.is_file() {
+            self.push(ConfigLayer::load(path)?);
+        }
+        Ok(())
+    }
+
+    pub fn validate_keys(&self, root: &Command) -> Result<(), AppError> {
+        for layer in &self.layers {
+            layer.validate_keys(root, &[], "", &layer.values)?;
+        }
+        Ok(())
+    }
+
+    /// Busca o valor de `flag` no comando em `command_path`, da camada de maior
+    /// precedência para a de menor, convertendo para o tipo da flag. Retorna
+    /// também o caminho do arquivo de onde o valor foi lido
+    pub fn lookup(
+        &self,
+        command_path: &[&str],
+        flag: &Flag,
+    ) -> Result<Option<(FlagValue, PathBuf)>, AppError> {
+        for layer in self.layers.iter().rev() {
+            if let Some(value) = layer.get(command_path, &flag.name) {
+                let mut key: Vec<&str> = command_path.to_vec();
+                key.push(&flag.name);
+
+                return to_flag_value(flag, value)
+                    .map(|value| Some((value, layer.path.clone())))
+                    .map_err(|message| layer.invalid_value(&key.join("."), message));
+            }
+        }
+
+        Ok(None)
+    }
+}
+
+fn user_config_dir(env: &EnvSource) -> Option<PathBuf> {
+    if let Some(dir) = env.get("XDG_CONFIG_HOME") {
+        return Some(PathBuf::from(dir));
+    }
+
+    env.get("HOME").map(|home| PathBuf::from(home).join(".config"))
+}
+
+fn describe(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(_) => "número",
+        Value::String(_) => "string",
+        Value::Array(_) => "lista",
+        Value::Object(_) => "tabela",
+    }
+}
+
+/// Converte um valor do arquivo para o tipo declarado em `flag.flag_type`
+pub(crate) fn to_flag_value(flag: &Flag, value: &Value) -> Result<FlagValue, String> {
+    let mismatch = || {
+        format!(
+            "esperado {}, encontrado {}",
+            flag.flag_type.description(),
+            describe(value)
+        )
+    };
+
+    match (&flag.flag_type, value) {
+        (FlagType::Bool, Value::Bool(b)) => Ok(FlagValue::Bool(*b)),
+        (FlagType::String, Value::String(s)) => flag.parse_value(s).map_err(|e| e.to_string()),
+        (FlagType::Integer | FlagType::Count, Value::Number(n)) => {
+            n.as_i64().map(FlagValue::Integer).ok_or_else(mismatch)
+        }
+        (FlagType::Float, Value::Number(n)) => n.as_f64().map(FlagValue::Float).ok_or_else(mismatch),
+        (FlagType::StringList, Value::String(s)) => {
+            flag.parse_value(s).map_err(|e| e.to_string())
+        }
+        (FlagType::StringList, Value::Array(items)) => {
+            let values = items
+                .iter()
+                .map(|item| item.as_str().map(str::to_string).ok_or_else(mismatch))
+                .collect::<Result<Vec<String>, String>>()?;
+            flag.parse_values(&values).map_err(|e| e.to_string())
+        }
+        (FlagType::IntegerList, Value::Number(n)) => n
+            .as_i64()
+            .map(|n| FlagValue::IntegerList(vec![n]))
+            .ok_or_else(mismatch),
+        (FlagType::IntegerList, Value::Array(items)) => items
+            .iter()
+            .map(|item| item.as_i64().ok_or_else(mismatch))
+            .collect::<Result<Vec<i64>, String>>()
+            .map(FlagValue::IntegerList),
+        _ => Err(mismatch()),
+    }
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,215 @@
+use super::*;
+use crate::parser::CLIParser;
+use crate::test_utils::TempDir;
+use std::fs;
+
+fn test_command() -> Command {
+    Command::new("app")
+        .show_help_on_empty(false)
+        .add_flag(Flag::new("endpoint", FlagType::String))
+        .add_flag(
+            Flag::new("timeout", FlagType::Integer)
+                .env("APP_TIMEOUT")
+                .default_value(FlagValue::Integer(30)),
+        )
+        .add_flag(Flag::new("config", FlagType::String).short('c'))
+        .add_flag(Flag::new("pattern", FlagType::String).allow_hyphen_values(true))
+        .add_flag(Flag::new("verbose", FlagType::Bool).short('v').global(true))
+        .add_subcommand(
+            Command::new("calc")
+                .add_subcommand(
+                    Command::new("add")
+                        .show_help_on_empty(false)
+                        .add_flag(Flag::new("numbers", FlagType::IntegerList))
+                        .add_flag(Flag::new("count", FlagType::Integer).short('c'))
+                        .add_flag(Flag::new("label", FlagType::String).allow_hyphen_values(true)),
+                ),
+        )
+}
+
+fn args(values: &[&str]) -> Vec<String> {
+    values.iter().map(|value| value.to_string()).collect()
+}
+
+#[test]
+fn test_string_list_value_is_split() {
+    let mut config = ConfigValues::new();
+    config.push(ConfigLayer::from_str("app.toml", "tags = \"a,b\"\n", ConfigFormat::Toml).unwrap());
+
+    let tags = Flag::new("tags", FlagType::StringList);
+    let (value, _) = config.lookup(&[], &tags).unwrap().unwrap();
+    assert_eq!(value, FlagValue::StringList(vec!["a".to_string(), "b".to_string()]));
+
+    let tags = tags.value_delimiter(None);
+    let (value, _) = config.lookup(&[], &tags).unwrap().unwrap();
+    assert_eq!(value, FlagValue::StringList(vec!["a,b".to_string()]));
+}
+
+#[test]
+fn test_formats_share_key_layout() {
+    let toml = ConfigLayer::from_str(
+        "app.toml",
+        "endpoint = \"https://api\"\n[calc.add]\nnumbers = [1, 2]\n",
+        ConfigFormat::Toml,
+    )
+    .unwrap();
+    let json = ConfigLayer::from_str(
+        "app.json",
+        r#"{"endpoint": "https://api", "calc": {"add": {"numbers": [1, 2]}}}"#,
+        ConfigFormat::Json,
+    )
+    .unwrap();
+    let yaml = ConfigLayer::from_str(
+        "app.yaml",
+        "endpoint: https://api\ncalc:\n  add:\n    numbers: [1, 2]\n",
+        ConfigFormat::Yaml,
+    )
+    .unwrap();
+
+    let numbers = Flag::new("numbers", FlagType::IntegerList);
+    for layer in [toml, json, yaml] {
+        let mut config = ConfigValues::new();
+        config.push(layer);
+
+        assert_eq!(
+            config.lookup(&["calc", "add"], &numbers).unwrap().map(|(value, _)| value),
+            Some(FlagValue::IntegerList(vec![1, 2]))
+        );
+        assert!(config.validate_keys(&test_command()).is_ok());
+    }
+}
+
+#[test]
+fn test_format_from_path() {
+    assert_eq!(ConfigFormat::from_path(Path::new("a.toml")), Some(ConfigFormat::Toml));
+    assert_eq!(ConfigFormat::from_path(Path::new("a.json")), Some(ConfigFormat::Json));
+    assert_eq!(ConfigFormat::from_path(Path::new("a.yml")), Some(ConfigFormat::Yaml));
+    assert_eq!(ConfigFormat::from_path(Path::new("a.ini")), None);
+}
+
+#[test]
+fn test_type_mismatch_cites_file_and_key() {
+    let mut config = ConfigValues::new();
+    config.push(
+        ConfigLayer::from_str("app.toml", "[calc.add]\nnumbers = \"um\"\n", ConfigFormat::Toml).unwrap(),
+    );
+
+    let numbers = Flag::new("numbers", FlagType::IntegerList);
+    let error = config.lookup(&["calc", "add"], &numbers).unwrap_err();
+
+    match error {
+        AppError::InvalidConfigValue { path, key, message } => {
+            assert_eq!(path, "app.toml");
+            assert_eq!(key, "calc.add.numbers");
+            assert!(message.contains("integer list"));
+        }
+        err => panic!("Erro esperado: InvalidConfigValue, erro recebido: {:?}", err),
+    }
+}
+
+#[test]
+fn test_unknown_key_is_rejected() {
+    let mut config = ConfigValues::new();
+    config.push(ConfigLayer::from_str("app.toml", "[calc]\nfoo = 1\n", ConfigFormat::Toml).unwrap());
+
+    let error = config.validate_keys(&test_command()).unwrap_err();
+    assert!(matches!(
+        error,
+        AppError::InvalidConfigValue { key, .. } if key == "calc.foo"
+    ));
+}
+
+#[test]
+fn test_global_flag_in_subcommand_table() {
+    let mut config = ConfigValues::new();
+    config.push(ConfigLayer::from_str("app.toml", "[calc]\nverbose = true\n", ConfigFormat::Toml).unwrap());
+    assert!(config.validate_keys(&test_command()).is_ok());
+
+    let env = EnvSource::default();
+    let parsed = CLIParser::parse_with_config(&test_command(), args(&["calc", "add"]), &env, &config).unwrap();
+    assert_eq!(parsed.get_flag("verbose").unwrap(), &FlagValue::Bool(true));
+
+    let parsed = CLIParser::parse_with_config(&test_command(), args(&[]), &env, &config).unwrap();
+    assert!(parsed.get_flag("verbose").is_none());
+}
+
+#[test]
+fn test_syntax_error_cites_file() {
+    let error = ConfigLayer::from_str("app.json", "{", ConfigFormat::Json).unwrap_err();
+    assert!(matches!(error, AppError::ConfigFileError { path, .. } if path == "app.json"));
+}
+
+#[test]
+fn test_root_flag_value() {
+    let command = test_command();
+    let find = |values: &[&str]| CLIParser::root_flag_value(&command, "config", &args(values));
+    let found = Some("a.toml".to_string());
+
+    assert_eq!(find(&["--config", "a.toml"]), found);
+    assert_eq!(find(&["--config=a.toml"]), found);
+    assert_eq!(find(&["-c", "a.toml", "calc"]), found);
+    assert_eq!(find(&["-ca.toml"]), found);
+    assert_eq!(find(&["-vc", "a.toml"]), found);
+    assert_eq!(find(&["-vca.toml"]), found);
+    assert_eq!(find(&["--", "--config", "a.toml"]), None);
+    assert_eq!(find(&["--configs", "a.toml"]), None);
+
+    // `-c` do subcomando e valores de outras flags não são o arquivo de configuração
+    assert_eq!(find(&["calc", "add", "-c", "3"]), None);
+    assert_eq!(find(&["-c", "a.toml", "calc", "add", "-c", "3"]), found);
+    assert_eq!(find(&["--pattern", "-c", "calc"]), None);
+    assert_eq!(find(&["calc", "add", "--label", "--config"]), None);
+}
+
+#[test]
+fn test_layer_precedence() {
+    let temp = TempDir::new("config-precedence");
+    let dir = temp.path();
+    let user_dir = dir.join("xdg").join("app");
+    fs::create_dir_all(&user_dir).unwrap();
+
+    fs::write(user_dir.join("config.toml"), "endpoint = \"user\"\ntimeout = 1\n").unwrap();
+    fs::write(dir.join("project.json"), r#"{"endpoint": "project"}"#).unwrap();
+    fs::write(dir.join("explicit.yaml"), "calc:\n  add:\n    numbers: [7]\n").unwrap();
+
+    let env = EnvSource::from_pairs([("XDG_CONFIG_HOME", dir.join("xdg").display().to_string())]);
+    let sources = vec![
+        ConfigSource::Flag("config".to_string()),
+        ConfigSource::File(dir.join("project.json")),
+        ConfigSource::File(dir.join("missing.toml")),
+        ConfigSource::UserConfig("config.toml".to_string()),
+    ];
+    let argv = args(&["-c", &dir.join("explicit.yaml").display().to_string(), "calc", "add"]);
+
+    let config = ConfigValues::load("app", &sources, &test_command(), &argv, &env).unwrap();
+    assert_eq!(config.layers.len(), 3);
+
+    let parsed = CLIParser::parse_with_config(&test_command(), argv, &env, &config).unwrap();
+    assert_eq!(parsed.get_flag("endpoint").unwrap(), &FlagValue::String("project".to_string()));
+    assert_eq!(parsed.get_flag("timeout").unwrap(), &FlagValue::Integer(1));
+    assert_eq!(parsed.get_flag("numbers").unwrap(), &FlagValue::IntegerList(vec![7]));
+}
+
+#[test]
+fn test_cli_and_env_override_config() {
+    let mut config = ConfigValues::new();
+    config.push(
+        ConfigLayer::from_str("app.toml", "endpoint = \"config\"\ntimeout = 1\n", ConfigFormat::Toml).unwrap(),
+    );
+    let env = EnvSource::from_pairs([("APP_TIMEOUT", "2")]);
+
+    let argv = args(&["--endpoint", "cli"]);
+    let parsed = CLIParser::parse_with_config(&test_command(), argv, &env, &config).unwrap();
+
+    assert_eq!(parsed.get_flag("endpoint").unwrap(), &FlagValue::String("cli".to_string()));
+    assert_eq!(parsed.get_flag("timeout").unwrap(), &FlagValue::Integer(2));
+}
+
+#[test]
+fn test_explicit_config_file_must_exist() {
+    let sources = vec![ConfigSource::Flag("config".to_string())];
+    let argv = args(&["--config", "/nao/existe/app.toml"]);
+
+    let result = ConfigValues::load("app", &sources, &test_command(), &argv, &EnvSource::default());
+    assert!(matches!(result.unwrap_err(), AppError::ConfigFileError { .. }));
+}
@@ -0,0 +1,485 @@
+//! Desserialização de `ParsedArgs` com serde, usada por `ParsedArgs::deserialize`
+
+use crate::ParsedArgs;
+use crate::flag::{FlagType, FlagValue};
+use serde::de::value::{BorrowedStrDeserializer, SeqDeserializer};
+use serde::de::{self, DeserializeSeed, EnumAccess, MapAccess, VariantAccess, Visitor};
+use std::fmt;
+
+/// Erro de desserialização. `path` são os campos em que ele ocorreu, do mais
+/// externo ao mais interno, ex: `["command", "title"]`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Error {
+    pub path: Vec<String>,
+    pub message: String,
+}
+
+impl Error {
+    fn new(message: impl Into<String>) -> Self {
+        Self {
+            path: Vec::new(),
+            message: message.into(),
+        }
+    }
+
+    fn in_field(mut self, field: &str) -> Self {
+        self.path.insert(0, field.to_string());
+        self
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.path.is_empty() {
+            write!(f, "{}", self.message)
+        } else {
+            write!(f, "campo `{}`: {}", self.path.join("."), self.message)
+        }
+    }
+}
+
+impl std::error::Error for Error {}
+
+impl de::Error for Error {
+    fn custom<T: fmt::Display>(message: T) -> Self {
+        Error::new(message.to_string())
+    }
+
+    fn invalid_type(unexpected: de::Unexpected, expected: &dyn de::Expected) -> Self {
+        Error::new(format!("esperado {}, recebido {}", expected, unexpected))
+    }
+
+    fn invalid_value(unexpected: de::Unexpected, expected: &dyn de::Expected) -> Self {
+        Error::new(format!("valor inválido {}, esperado {}", unexpected, expected))
+    }
+
+    fn unknown_variant(variant: &str, expected: &'static [&'static str]) -> Self {
+        Error::new(format!(
+            "valor desconhecido '{}', esperado um de: {}",
+            variant,
+            expected.join(", ")
+        ))
+    }
+
+    fn missing_field(field: &'static str) -> Self {
+        Error::new("valor obrigatório não informado").in_field(field)
+    }
+}
+
+/// Desserializa um nível de `ParsedArgs`:
+///
+/// - structs: cada campo recebe a flag de mesmo nome (`dry_run` ou `dry-run`), senão o
+///   argumento posicional de mesmo nome. Os campos `command` e `subcommand`, quando
+///   não são flags, recebem o subcomando escolhido. Flags `Bool` e listas não
+///   informadas viram `false` e lista vazia; os demais campos ausentes ficam a cargo
+///   do serde (`None` em `Option`, `#[serde(default)]`, senão um erro)
+/// - enums: a variante com o nome do subcomando, com os campos lidos do nível do
+///   subcomando (use `#[serde(rename_all = "kebab-case")]` para casar os nomes)
+/// - mapas: todas as flags e argumentos posicionais deste nível
+pub struct ParsedArgsDeserializer<'de> {
+    parsed: &'de ParsedArgs,
+}
+
+impl<'de> ParsedArgsDeserializer<'de> {
+    pub fn new(parsed: &'de ParsedArgs) -> Self {
+        Self { parsed }
+    }
+}
+
+/// Valor de um campo, encontrado em `lookup`
+enum Field<'de> {
+    Flag(&'de FlagValue),
+    Positional(&'de str),
+    Subcommand(&'de ParsedArgs),
+    Missing,
+}
+
+impl<'de> Field<'de> {
+    fn lookup(parsed: &'de ParsedArgs, name: &str) -> Self {
+        let kebab = name.replace('_', "-");
+
+        if let Some(value) = parsed.flags.get(name).or_else(|| parsed.flags.get(&kebab)) {
+            return Field::Flag(value);
+        }
+
+        if let Some(value) = parsed
+            .get_arg_by_name(name)
+            .or_else(|| parsed.get_arg_by_name(&kebab))
+        {
+            return Field::Positional(value);
+        }
+
+        if name == "command" || name == "subcommand" {
+            return Field::Subcommand(parsed);
+        }
+
+        Field::Missing
+    }
+
+    /// Se o campo é entregue ao visitor. Campos ausentes ficam de fora para que o
+    /// serde aplique `#[serde(default)]`, exceto as flags `Bool` e listas declaradas
+    fn is_provided(parsed: &'de ParsedArgs, name: &str) -> bool {
+        if !matches!(Self::lookup(parsed, name), Field::Missing) {
+            return true;
+        }
+
+        let kebab = name.replace('_', "-");
+        parsed
+            .flag_types
+            .get(name)
+            .or_else(|| parsed.flag_types.get(&kebab))
+            .is_some_and(|flag_type| matches!(flag_type, FlagType::Bool) || flag_type.is_list())
+    }
+
+    fn deserialize<S>(self, seed: S) -> Result<S::Value, Error>
+    where
+        S: DeserializeSeed<'de>,
+    {
+        match self {
+            Field::Flag(value) => seed.deserialize(FlagValueDeserializer(value)),
+            Field::Positional(value) => seed.deserialize(RawDeserializer(value)),
+            Field::Subcommand(parsed) => seed.deserialize(SubcommandDeserializer(parsed)),
+            Field::Missing => seed.deserialize(MissingDeserializer),
+        }
+    }
+}
+
+impl<'de> de::Deserializer<'de> for ParsedArgsDeserializer<'de> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        let mut names: Vec<&'de str> = self.parsed.flags.keys().map(String::as_str).collect();
+        names.sort();
+        names.extend(
+            self.parsed
+                .positional_names
+                .iter()
+                .take(self.parsed.positional_args.len())
+                .map(String::as_str),
+        );
+
+        visitor.visit_map(FieldsAccess::new(self.parsed, names))
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        let names = fields
+            .iter()
+            .copied()
+            .filter(|name| Field::is_provided(self.parsed, name))
+            .collect();
+
+        visitor.visit_map(FieldsAccess::new(self.parsed, names))
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        SubcommandDeserializer(self.parsed).deserialize_any(visitor)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map identifier ignored_any
+    }
+}
+
+/// Entrega os campos `names` de `parsed` como um mapa
+struct FieldsAccess<'de> {
+    parsed: &'de ParsedArgs,
+    names: std::vec::IntoIter<&'de str>,
+    current: Option<&'de str>,
+}
+
+impl<'de> FieldsAccess<'de> {
+    fn new(parsed: &'de ParsedArgs, names: Vec<&'de str>) -> Self {
+        Self {
+            parsed,
+            names: names.into_iter(),
+            current: None,
+        }
+    }
+}
+
+impl<'de> MapAccess<'de> for FieldsAccess<'de> {
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Error>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        let Some(name) = self.names.next() else {
+            return Ok(None);
+        };
+        self.current = Some(name);
+        seed.deserialize(BorrowedStrDeserializer::new(name)).map(Some)
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let name = self
+            .current
+            .take()
+            .ok_or_else(|| Error::new("valor pedido antes da chave"))?;
+
+        Field::lookup(self.parsed, name)
+            .deserialize(seed)
+            .map_err(|error| error.in_field(name))
+    }
+}
+
+/// Valor de uma flag, com o tipo de `FlagValue`
+struct FlagValueDeserializer<'de>(&'de FlagValue);
+
+impl<'de> de::Deserializer<'de> for FlagValueDeserializer<'de> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match self.0 {
+            FlagValue::Bool(value) => visitor.visit_bool(*value),
+            FlagValue::String(value) => visitor.visit_borrowed_str(value),
+            FlagValue::Integer(value) => visitor.visit_i64(*value),
+            FlagValue::Float(value) => visitor.visit_f64(*value),
+            FlagValue::StringList(list) => {
+                let items = list.iter().map(|item| BorrowedStrDeserializer::new(item.as_str()));
+                SeqDeserializer::new(items).deserialize_any(visitor)
+            }
+            FlagValue::IntegerList(list) => {
+                SeqDeserializer::new(list.iter().copied()).deserialize_any(visitor)
+            }
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        match self.0 {
+            FlagValue::String(value) => visitor.visit_enum(BorrowedStrDeserializer::new(value)),
+            _ => self.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct seq tuple tuple_struct map struct identifier ignored_any
+    }
+}
+
+/// Argumento posicional, convertido do texto para o tipo pedido pelo campo
+struct RawDeserializer<'de>(&'de str);
+
+macro_rules! deserialize_parsed {
+    ($($method:ident => $visit:ident: $ty:ty,)*) => {
+        $(
+            fn $method<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+                match self.0.parse::<$ty>() {
+                    Ok(value) => visitor.$visit(value),
+                    Err(_) => Err(de::Error::invalid_value(de::Unexpected::Str(self.0), &visitor)),
+                }
+            }
+        )*
+    };
+}
+
+impl<'de> de::Deserializer<'de> for RawDeserializer<'de> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_borrowed_str(self.0)
+    }
+
+    deserialize_parsed! {
+        deserialize_bool => visit_bool: bool,
+        deserialize_i8 => visit_i8: i8,
+        deserialize_i16 => visit_i16: i16,
+        deserialize_i32 => visit_i32: i32,
+        deserialize_i64 => visit_i64: i64,
+        deserialize_i128 => visit_i128: i128,
+        deserialize_u8 => visit_u8: u8,
+        deserialize_u16 => visit_u16: u16,
+        deserialize_u32 => visit_u32: u32,
+        deserialize_u64 => visit_u64: u64,
+        deserialize_u128 => visit_u128: u128,
+        deserialize_f32 => visit_f32: f32,
+        deserialize_f64 => visit_f64: f64,
+        deserialize_char => visit_char: char,
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_some(self)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        _variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_enum(BorrowedStrDeserializer::new(self.0))
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    serde::forward_to_deserialize_any! {
+        str string bytes byte_buf unit unit_struct seq tuple tuple_struct map struct
+        identifier ignored_any
+    }
+}
+
+/// Subcomando escolhido em um nível, como enum
+struct SubcommandDeserializer<'de>(&'de ParsedArgs);
+
+impl<'de> de::Deserializer<'de> for SubcommandDeserializer<'de> {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        match (&self.0.subcommand, self.0.subcommand_matches()) {
+            (Some(name), Some(matches)) => visitor.visit_enum(SubcommandAccess { name, matches }),
+            _ => Err(Error::new("nenhum subcomando informado")),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        if self.0.subcommand_matches().is_some() {
+            visitor.visit_some(self)
+        } else {
+            visitor.visit_none()
+        }
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit unit_struct newtype_struct seq tuple tuple_struct map struct
+        enum identifier ignored_any
+    }
+}
+
+struct SubcommandAccess<'de> {
+    name: &'de str,
+    matches: &'de ParsedArgs,
+}
+
+impl<'de> EnumAccess<'de> for SubcommandAccess<'de> {
+    type Error = Error;
+    type Variant = ParsedArgsDeserializer<'de>;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self::Variant), Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let variant = seed.deserialize(BorrowedStrDeserializer::new(self.name))?;
+        Ok((variant, ParsedArgsDeserializer::new(self.matches)))
+    }
+}
+
+impl<'de> VariantAccess<'de> for ParsedArgsDeserializer<'de> {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value, Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        seed.deserialize(self)
+    }
+
+    fn tuple_variant<V: Visitor<'de>>(self, _len: usize, _visitor: V) -> Result<V::Value, Error> {
+        Err(Error::new(format!(
+            "o subcomando {} não pode ser uma variante de tupla",
+            self.parsed.command
+        )))
+    }
+
+    fn struct_variant<V: Visitor<'de>>(
+        self,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Error> {
+        visitor.visit_map(FieldsAccess::new(self.parsed, fields.to_vec()))
+    }
+}
+
+/// Campo sem flag nem argumento: `false`, `None` ou lista vazia; erro nos demais tipos
+struct MissingDeserializer;
+
+impl<'de> de::Deserializer<'de> for MissingDeserializer {
+    type Error = Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, _visitor: V) -> Result<V::Value, Error> {
+        Err(Error::new("valor obrigatório não informado"))
+    }
+
+    fn deserialize_bool<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_bool(false)
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_none()
+    }
+
+    fn deserialize_seq<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        SeqDeserializer::new(std::iter::empty::<i64>()).deserialize_any(visitor)
+    }
+
+    fn deserialize_unit<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_unit()
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Error> {
+        visitor.visit_unit()
+    }
+
+    serde::forward_to_deserialize_any! {
+        i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+        bytes byte_buf unit_struct newtype_struct tuple tuple_struct map struct enum
+        identifier
+    }
+}
+
+#[cfg(test)]
+mod tests;
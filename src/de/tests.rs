@@ -0,0 +1,190 @@
+use crate::command::PositionalArg;
+use crate::{App, AppError, Command, Flag, FlagType};
+use serde::Deserialize;
+use std::collections::HashMap;
+
+fn test_app() -> App {
+    App::new("tasks", "1.0.0")
+        .add_global_flag(Flag::new("verbose", FlagType::Bool).short('v'))
+        .add_global_flag(Flag::new("dry-run", FlagType::Bool))
+        .add_command(
+            Command::new("add")
+                .add_flag(Flag::new("priority", FlagType::String).possible_values(vec![
+                    "low".to_string(),
+                    "high".to_string(),
+                ]))
+                .add_flag(Flag::new("tags", FlagType::StringList))
+                .add_flag(Flag::new("estimate", FlagType::Float))
+                .add_positional_arg(PositionalArg::new("title"))
+                .add_positional_arg(PositionalArg::new("points").required(false)),
+        )
+        .add_command(
+            Command::new("remove").add_flag(Flag::new("ids", FlagType::IntegerList).required(true)),
+        )
+        .add_command(Command::new("clean").show_help_on_empty(false))
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct Cli {
+    verbose: bool,
+    dry_run: bool,
+    command: Option<Commands>,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "kebab-case")]
+enum Commands {
+    Add(AddArgs),
+    Remove { ids: Vec<u32> },
+    Clean,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct AddArgs {
+    title: String,
+    points: Option<u8>,
+    priority: Option<Priority>,
+    tags: Vec<String>,
+    estimate: Option<f32>,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+enum Priority {
+    Low,
+    High,
+}
+
+#[test]
+fn test_deserialize_struct_with_subcommand() {
+    let parsed = test_app()
+        .parse(["add", "Escrever docs", "3", "--priority", "high", "--tags", "docs", "--dry-run"])
+        .unwrap();
+
+    let cli: Cli = parsed.deserialize().unwrap();
+    assert_eq!(
+        cli,
+        Cli {
+            verbose: false,
+            dry_run: true,
+            command: Some(Commands::Add(AddArgs {
+                title: "Escrever docs".to_string(),
+                points: Some(3),
+                priority: Some(Priority::High),
+                tags: vec!["docs".to_string()],
+                estimate: None,
+            })),
+        }
+    );
+}
+
+#[test]
+fn test_deserialize_struct_and_unit_variants() {
+    let parsed = test_app().parse(["-v", "remove", "--ids", "1,2"]).unwrap();
+    let cli: Cli = parsed.deserialize().unwrap();
+    assert!(cli.verbose);
+    assert_eq!(cli.command, Some(Commands::Remove { ids: vec![1, 2] }));
+
+    let parsed = test_app().parse(["clean"]).unwrap();
+    let cli: Cli = parsed.deserialize().unwrap();
+    assert_eq!(cli.command, Some(Commands::Clean));
+
+    let parsed = test_app().parse(["-v"]).unwrap();
+    let cli: Cli = parsed.deserialize().unwrap();
+    assert_eq!(cli.command, None);
+}
+
+#[test]
+fn test_deserialize_subcommand_level_directly() {
+    let parsed = test_app().parse(["add", "Tarefa"]).unwrap();
+
+    let command: Commands = parsed.deserialize().unwrap();
+    assert!(matches!(command, Commands::Add(AddArgs { ref title, .. }) if title == "Tarefa"));
+
+    let args: AddArgs = parsed.subcommand_matches().unwrap().deserialize().unwrap();
+    assert_eq!(args.tags, Vec::<String>::new());
+}
+
+#[test]
+fn test_deserialize_into_map() {
+    let parsed = test_app().parse(["add", "Tarefa", "--priority", "low"]).unwrap();
+    let map: HashMap<String, String> = parsed.subcommand_matches().unwrap().deserialize().unwrap();
+
+    assert_eq!(map["priority"], "low");
+    assert_eq!(map["title"], "Tarefa");
+}
+
+#[test]
+fn test_type_mismatch_names_field() {
+    let parsed = test_app().parse(["add", "Tarefa", "abc"]).unwrap();
+
+    let error = parsed.deserialize::<Cli>().unwrap_err();
+    let AppError::ParseError { message } = error else {
+        panic!("esperado ParseError, recebido {:?}", error);
+    };
+    assert!(message.starts_with("campo `command.points`:"), "{}", message);
+}
+
+#[test]
+fn test_missing_required_field_names_field() {
+    #[derive(Debug, Deserialize)]
+    struct Strict {
+        #[allow(dead_code)]
+        estimate: f64,
+    }
+
+    let parsed = test_app().parse(["add", "Tarefa"]).unwrap();
+    let error = parsed.subcommand_matches().unwrap().deserialize::<Strict>().unwrap_err();
+
+    assert_eq!(
+        error,
+        AppError::ParseError {
+            message: "campo `estimate`: valor obrigatório não informado".to_string()
+        }
+    );
+}
+
+#[test]
+fn test_missing_subcommand_for_enum() {
+    let parsed = test_app().parse(["-v"]).unwrap();
+    let error = parsed.deserialize::<Commands>().unwrap_err();
+
+    assert_eq!(
+        error,
+        AppError::ParseError {
+            message: "nenhum subcomando informado".to_string()
+        }
+    );
+}
+
+#[test]
+fn test_serde_default_for_absent_fields() {
+    fn default_priority() -> String {
+        "low".to_string()
+    }
+
+    #[derive(Debug, Deserialize, PartialEq)]
+    struct WithDefaults {
+        title: String,
+        #[serde(default)]
+        times: i64,
+        #[serde(default = "default_priority")]
+        priority: String,
+        tags: Vec<String>,
+        verbose: bool,
+    }
+
+    let parsed = test_app().parse(["add", "Tarefa"]).unwrap();
+    let args: WithDefaults = parsed.subcommand_matches().unwrap().deserialize().unwrap();
+
+    assert_eq!(
+        args,
+        WithDefaults {
+            title: "Tarefa".to_string(),
+            times: 0,
+            priority: "low".to_string(),
+            tags: Vec::new(),
+            verbose: false,
+        }
+    );
+}
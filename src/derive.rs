@@ -0,0 +1,214 @@
+//! Traits implementados por `#[derive(Parser)]`, `#[derive(Subcommand)]` e
+//! `#[derive(ValueEnum)]` (feature `derive`), que também podem ser implementados
+//! manualmente
+
+use crate::flag::FlagValue;
+use crate::ui::ColoredUI;
+use crate::{App, AppError, Command, Flag, FlagType, ParsedArgs};
+
+/// Struct cujos campos viram flags, argumentos posicionais e subcomandos de um comando
+pub trait Args: Sized {
+    /// Adiciona ao comando as flags, argumentos e subcomandos do tipo
+    fn augment_command(command: Command) -> Command;
+
+    /// Constrói o valor a partir do nível de `ParsedArgs` correspondente ao comando
+    fn from_parsed(parsed: &ParsedArgs) -> Result<Self, AppError>;
+}
+
+/// Struct que define a aplicação inteira
+pub trait Parser: Args {
+    fn app() -> App;
+
+    /// Faz o parsing de `args` (sem o nome do programa) e constrói o valor. Retorna
+    /// `ConfigurationError` se `App::validate` falhar e `HelpRequested` se a ajuda
+    /// foi pedida
+    fn try_parse_from<I, S>(args: I) -> Result<Self, AppError>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let app = Self::app();
+        app.validate()?;
+
+        let parsed = app.parse(args)?;
+        if parsed.help_requested {
+            return Err(AppError::HelpRequested);
+        }
+        Self::from_parsed(&parsed)
+    }
+
+    /// Faz o parsing dos argumentos do processo com `App::run`, que exibe ajuda e
+    /// erros. Encerra o processo com código 0 se a ajuda foi pedida e 2 em caso de erro
+    fn parse() -> Self {
+        let app = Self::app();
+        if let Err(error) = app.validate() {
+            ColoredUI::show_error(&error);
+            std::process::exit(2);
+        }
+
+        let parsed = match app.run_from_env() {
+            Ok(parsed) => parsed,
+            Err(_) => std::process::exit(2),
+        };
+
+        if parsed.help_requested {
+            std::process::exit(0);
+        }
+
+        Self::from_parsed(&parsed).unwrap_or_else(|error| {
+            ColoredUI::show_error(&error);
+            std::process::exit(2);
+        })
+    }
+}
+
+/// Enum cujas variantes viram subcomandos
+pub trait Subcommand: Sized {
+    fn augment_subcommands(command: Command) -> Command;
+
+    /// Constrói a variante a partir do subcomando escolhido em `parsed`, ou `None`
+    /// se nenhum subcomando foi informado
+    fn from_parsed(parsed: &ParsedArgs) -> Result<Option<Self>, AppError>;
+}
+
+/// Enum cujas variantes são os valores possíveis de uma flag
+pub trait ValueEnum: Sized {
+    fn possible_values() -> Vec<String>;
+
+    fn from_value(value: &str) -> Option<Self>;
+}
+
+/// Tipos que podem ser o valor de uma flag ou argumento posicional
+pub trait FlagArg: Sized {
+    fn flag_type() -> FlagType;
+
+    fn possible_values() -> Option<Vec<String>> {
+        None
+    }
+
+    fn from_flag_value(value: &FlagValue) -> Option<Self>;
+
+    /// Valor usado quando a flag não é informada, ex: `false` para `bool`
+    fn missing() -> Option<Self> {
+        None
+    }
+}
+
+impl FlagArg for bool {
+    fn flag_type() -> FlagType {
+        FlagType::Bool
+    }
+
+    fn from_flag_value(value: &FlagValue) -> Option<Self> {
+        value.as_bool()
+    }
+
+    fn missing() -> Option<Self> {
+        Some(false)
+    }
+}
+
+impl FlagArg for String {
+    fn flag_type() -> FlagType {
+        FlagType::String
+    }
+
+    fn from_flag_value(value: &FlagValue) -> Option<Self> {
+        value.as_string().map(str::to_string)
+    }
+}
+
+impl FlagArg for i64 {
+    fn flag_type() -> FlagType {
+        FlagType::Integer
+    }
+
+    fn from_flag_value(value: &FlagValue) -> Option<Self> {
+        value.as_integer()
+    }
+}
+
+impl FlagArg for f64 {
+    fn flag_type() -> FlagType {
+        FlagType::Float
+    }
+
+    fn from_flag_value(value: &FlagValue) -> Option<Self> {
+        value.as_float()
+    }
+}
+
+impl FlagArg for Vec<String> {
+    fn flag_type() -> FlagType {
+        FlagType::StringList
+    }
+
+    fn from_flag_value(value: &FlagValue) -> Option<Self> {
+        value.as_string_list().cloned()
+    }
+
+    fn missing() -> Option<Self> {
+        Some(Vec::new())
+    }
+}
+
+impl FlagArg for Vec<i64> {
+    fn flag_type() -> FlagType {
+        FlagType::IntegerList
+    }
+
+    fn from_flag_value(value: &FlagValue) -> Option<Self> {
+        value.as_integer_list().cloned()
+    }
+
+    fn missing() -> Option<Self> {
+        Some(Vec::new())
+    }
+}
+
+/// Flag com o tipo e os valores possíveis de `T`
+pub fn flag_for<T: FlagArg>(name: &str) -> Flag {
+    let flag = Flag::new(name, T::flag_type());
+
+    match T::possible_values() {
+        Some(values) => flag.possible_values(values),
+        None => flag,
+    }
+}
+
+/// Aplica o `default_value` de `#[arg]` à flag. Um valor que não corresponde ao
+/// tipo da flag fica como texto, e `App::validate` (chamado por `Parser::parse` e
+/// `Parser::try_parse_from`) o rejeita com `ConfigurationError`
+pub fn default_value(flag: Flag, raw: &str) -> Flag {
+    let value = flag
+        .parse_value(raw)
+        .unwrap_or_else(|_| FlagValue::String(raw.to_string()));
+    flag.default_value(value)
+}
+
+/// Valor da flag `name` neste nível de `parsed`, convertido para `T`
+pub fn flag_value<T: FlagArg>(parsed: &ParsedArgs, name: &str) -> Result<Option<T>, AppError> {
+    let Some(value) = parsed.flags.get(name) else {
+        return Ok(None);
+    };
+
+    T::from_flag_value(value).map(Some).ok_or_else(|| AppError::ParseError {
+        message: format!("Valor da flag --{} não corresponde ao tipo do campo", name),
+    })
+}
+
+/// Argumento posicional `index` deste nível de `parsed`, convertido para `T`
+pub fn positional_value<T: FlagArg>(
+    parsed: &ParsedArgs,
+    index: usize,
+    name: &str,
+) -> Result<Option<T>, AppError> {
+    let Some(raw) = parsed.positional_args.get(index) else {
+        return Ok(None);
+    };
+
+    let value = flag_for::<T>(name).parse_value(raw)?;
+    T::from_flag_value(&value).map(Some).ok_or_else(|| AppError::ParseError {
+        message: format!("Valor do argumento <{}> não corresponde ao tipo do campo", name),
+    })
+}
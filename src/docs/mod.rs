@@ -0,0 +1,425 @@
+use crate::command::Example;
+use crate::man::page_name;
+use crate::ui::ColoredUI;
+use crate::{App, AppError, Command, Flag};
+use std::path::{Path, PathBuf};
+
+/// Formatos da documentação de referência
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DocFormat {
+    Markdown,
+    /// Páginas HTML completas, sem dependências externas
+    Html,
+}
+
+impl DocFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            DocFormat::Markdown => "md",
+            DocFormat::Html => "html",
+        }
+    }
+}
+
+/// Página de documentação de um comando
+#[derive(Debug, Clone, PartialEq)]
+pub struct DocPage {
+    /// Nome do arquivo, ex: `app-calc-add.md`
+    pub file_name: String,
+    pub content: String,
+}
+
+/// Conteúdo de uma página, independente do formato
+struct PageModel {
+    title: String,
+    version: Option<String>,
+    description: String,
+    usage: String,
+    parent: Option<(String, String)>,
+    /// Nome, arquivo e descrição de cada subcomando visível
+    subcommands: Vec<(String, String, String)>,
+    arguments: Vec<Vec<String>>,
+    options: Vec<Vec<String>>,
+    global_options: Vec<Vec<String>>,
+    examples: Vec<Example>,
+}
+
+const ARGUMENT_HEADERS: [&str; 3] = ["Argumento", "Obrigatório", "Descrição"];
+const OPTION_HEADERS: [&str; 5] = ["Flag", "Tipo", "Obrigatória", "Padrão", "Descrição"];
+
+/// Gera uma página por comando visível da aplicação, começando pela raiz.
+/// A saída não depende de data ou ordem de inserção, para poder ser versionada
+pub fn render(app: &App, format: DocFormat) -> Vec<DocPage> {
+    let mut pages = Vec::new();
+    render_tree(app, &app.root_command, &mut Vec::new(), format, &mut pages);
+    pages
+}
+
+fn render_tree<'a>(
+    app: &App,
+    command: &'a Command,
+    path: &mut Vec<&'a str>,
+    format: DocFormat,
+    pages: &mut Vec<DocPage>,
+) {
+    let model = page_model(app, path, command, format);
+    let content = match format {
+        DocFormat::Markdown => render_markdown(&model),
+        DocFormat::Html => render_html(&model),
+    };
+
+    pages.push(DocPage {
+        file_name: file_name(app, path, format),
+        content,
+    });
+
+    for subcommand in command.get_visible_subcommands() {
+        path.push(&subcommand.name);
+        render_tree(app, subcommand, path, format, pages);
+        path.pop();
+    }
+}
+
+/// Escreve as páginas de `render` em `dir` (criado se necessário) e retorna os
+/// caminhos gerados
+pub fn write_to_dir(
+    app: &App,
+    dir: impl AsRef<Path>,
+    format: DocFormat,
+) -> Result<Vec<PathBuf>, AppError> {
+    let dir = dir.as_ref();
+    std::fs::create_dir_all(dir)?;
+
+    let mut written = Vec::new();
+    for page in render(app, format) {
+        let path = dir.join(&page.file_name);
+        std::fs::write(&path, page.content)?;
+        written.push(path);
+    }
+
+    Ok(written)
+}
+
+fn file_name(app: &App, path: &[&str], format: DocFormat) -> String {
+    format!("{}.{}", page_name(app, path), format.extension())
+}
+
+fn page_model(app: &App, path: &[&str], command: &Command, format: DocFormat) -> PageModel {
+    let title = std::iter::once(app.name.as_str())
+        .chain(path.iter().copied())
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    let (version, description) = if path.is_empty() {
+        (Some(app.version.clone()), app.description.clone())
+    } else {
+        (None, command.description.clone())
+    };
+
+    let parent = path.split_last().map(|(_, parent)| {
+        let name = std::iter::once(app.name.as_str())
+            .chain(parent.iter().copied())
+            .collect::<Vec<_>>()
+            .join(" ");
+        (name, file_name(app, parent, format))
+    });
+
+    let subcommands = command
+        .get_visible_subcommands()
+        .into_iter()
+        .map(|subcommand| {
+            let mut child = path.to_vec();
+            child.push(&subcommand.name);
+            (
+                subcommand.name.clone(),
+                file_name(app, &child, format),
+                subcommand.description.clone(),
+            )
+        })
+        .collect();
+
+    let arguments = command
+        .positional_args
+        .iter()
+        .map(|arg| {
+            vec![
+                arg.name.clone(),
+                yes_no(arg.required).to_string(),
+                arg.description.clone(),
+            ]
+        })
+        .collect();
+
+    PageModel {
+        title,
+        version,
+        description,
+        usage: ColoredUI::format_usage(&app.name, path, command),
+        parent,
+        subcommands,
+        arguments,
+        options: command.get_flags_sorted().into_iter().map(option_row).collect(),
+        global_options: app
+            .root_command
+            .inherited_global_flags(path)
+            .into_iter()
+            .map(option_row)
+            .collect(),
+        examples: command.examples.clone(),
+    }
+}
+
+fn yes_no(value: bool) -> &'static str {
+    if value { "sim" } else { "não" }
+}
+
+fn option_row(flag: &Flag) -> Vec<String> {
+    let mut names = Vec::new();
+    if let Some(short) = flag.short {
+        names.push(format!("-{}", short));
+    }
+    names.push(format!("--{}", flag.display_name()));
+
+    let flag_type = if flag.flag_type.takes_value() {
+        flag.flag_type.description().to_string()
+    } else {
+        String::new()
+    };
+
+    let default = flag
+        .default_value
+        .as_ref()
+        .map(|value| value.to_string())
+        .unwrap_or_default();
+
+    let mut description = vec![flag.description.clone()];
+    if let Some(ref possible) = flag.possible_values {
+        description.push(format!("Valores possíveis: {}.", possible.join(", ")));
+    }
+    if let Some(ref env) = flag.env {
+        description.push(format!("Variável de ambiente: {}.", env));
+    }
+    if let Some(ref default_missing) = flag.default_missing_value {
+        description.push(format!("Sem valor: {}.", default_missing));
+    }
+
+    vec![
+        names.join(", "),
+        flag_type,
+        yes_no(flag.required).to_string(),
+        default,
+        description
+            .into_iter()
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join(" "),
+    ]
+}
+
+/// Escapa texto para uma célula de tabela Markdown
+fn markdown_cell(text: &str) -> String {
+    text.replace('|', "\\|").replace('\n', " ")
+}
+
+fn markdown_table(headers: &[&str], rows: &[Vec<String>], code_column: Option<usize>) -> String {
+    let mut table = format!("| {} |\n", headers.join(" | "));
+    table.push_str(&format!("|{}\n", " --- |".repeat(headers.len())));
+
+    for row in rows {
+        let cells: Vec<String> = row
+            .iter()
+            .enumerate()
+            .map(|(index, cell)| {
+                if Some(index) == code_column && !cell.is_empty() {
+                    format!("`{}`", markdown_cell(cell))
+                } else {
+                    markdown_cell(cell)
+                }
+            })
+            .collect();
+        table.push_str(&format!("| {} |\n", cells.join(" | ")));
+    }
+
+    table
+}
+
+fn render_markdown(model: &PageModel) -> String {
+    let mut page = format!("# {}\n\n", model.title);
+
+    if let Some(ref version) = model.version {
+        page.push_str(&format!("Versão {}\n\n", version));
+    }
+
+    if !model.description.is_empty() {
+        page.push_str(&format!("{}\n\n", model.description));
+    }
+
+    page.push_str(&format!("## Uso\n\n```text\n{}\n```\n\n", model.usage));
+
+    if !model.subcommands.is_empty() {
+        page.push_str("## Subcomandos\n\n");
+        let rows: Vec<Vec<String>> = model
+            .subcommands
+            .iter()
+            .map(|(name, file, description)| {
+                vec![format!("[`{}`]({})", name, file), markdown_cell(description)]
+            })
+            .collect();
+
+        page.push_str("| Comando | Descrição |\n| --- | --- |\n");
+        for row in rows {
+            page.push_str(&format!("| {} |\n", row.join(" | ")));
+        }
+        page.push('\n');
+    }
+
+    if !model.arguments.is_empty() {
+        page.push_str("## Argumentos\n\n");
+        page.push_str(&markdown_table(&ARGUMENT_HEADERS, &model.arguments, Some(0)));
+        page.push('\n');
+    }
+
+    if !model.options.is_empty() {
+        page.push_str("## Opções\n\n");
+        page.push_str(&markdown_table(&OPTION_HEADERS, &model.options, Some(0)));
+        page.push('\n');
+    }
+
+    if !model.global_options.is_empty() {
+        page.push_str("## Opções globais\n\n");
+        page.push_str(&markdown_table(&OPTION_HEADERS, &model.global_options, Some(0)));
+        page.push('\n');
+    }
+
+    if !model.examples.is_empty() {
+        page.push_str("## Exemplos\n\n");
+        for example in &model.examples {
+            if !example.description.is_empty() {
+                page.push_str(&format!("{}\n\n", example.description));
+            }
+            page.push_str(&format!("```sh\n{}\n```\n\n", example.command));
+        }
+    }
+
+    if let Some((ref name, ref file)) = model.parent {
+        page.push_str(&format!("Veja também: [`{}`]({})\n", name, file));
+    }
+
+    format!("{}\n", page.trim_end())
+}
+
+fn html_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+fn html_table(headers: &[&str], rows: &[Vec<String>], code_column: Option<usize>) -> String {
+    let mut table = String::from("<table>\n<thead><tr>");
+    for header in headers {
+        table.push_str(&format!("<th>{}</th>", html_escape(header)));
+    }
+    table.push_str("</tr></thead>\n<tbody>\n");
+
+    for row in rows {
+        table.push_str("<tr>");
+        for (index, cell) in row.iter().enumerate() {
+            if Some(index) == code_column && !cell.is_empty() {
+                table.push_str(&format!("<td><code>{}</code></td>", html_escape(cell)));
+            } else {
+                table.push_str(&format!("<td>{}</td>", html_escape(cell)));
+            }
+        }
+        table.push_str("</tr>\n");
+    }
+
+    table.push_str("</tbody>\n</table>\n");
+    table
+}
+
+fn render_html(model: &PageModel) -> String {
+    let mut body = format!("<h1>{}</h1>\n", html_escape(&model.title));
+
+    if let Some(ref version) = model.version {
+        body.push_str(&format!("<p>Versão {}</p>\n", html_escape(version)));
+    }
+
+    if !model.description.is_empty() {
+        body.push_str(&format!("<p>{}</p>\n", html_escape(&model.description)));
+    }
+
+    body.push_str(&format!(
+        "<h2>Uso</h2>\n<pre><code>{}</code></pre>\n",
+        html_escape(&model.usage)
+    ));
+
+    if !model.subcommands.is_empty() {
+        body.push_str("<h2>Subcomandos</h2>\n<table>\n<thead><tr><th>Comando</th><th>Descrição</th></tr></thead>\n<tbody>\n");
+        for (name, file, description) in &model.subcommands {
+            body.push_str(&format!(
+                "<tr><td><a href=\"{}\"><code>{}</code></a></td><td>{}</td></tr>\n",
+                html_escape(file),
+                html_escape(name),
+                html_escape(description)
+            ));
+        }
+        body.push_str("</tbody>\n</table>\n");
+    }
+
+    if !model.arguments.is_empty() {
+        body.push_str("<h2>Argumentos</h2>\n");
+        body.push_str(&html_table(&ARGUMENT_HEADERS, &model.arguments, Some(0)));
+    }
+
+    if !model.options.is_empty() {
+        body.push_str("<h2>Opções</h2>\n");
+        body.push_str(&html_table(&OPTION_HEADERS, &model.options, Some(0)));
+    }
+
+    if !model.global_options.is_empty() {
+        body.push_str("<h2>Opções globais</h2>\n");
+        body.push_str(&html_table(&OPTION_HEADERS, &model.global_options, Some(0)));
+    }
+
+    if !model.examples.is_empty() {
+        body.push_str("<h2>Exemplos</h2>\n");
+        for example in &model.examples {
+            if !example.description.is_empty() {
+                body.push_str(&format!("<p>{}</p>\n", html_escape(&example.description)));
+            }
+            body.push_str(&format!("<pre><code>{}</code></pre>\n", html_escape(&example.command)));
+        }
+    }
+
+    if let Some((ref name, ref file)) = model.parent {
+        body.push_str(&format!(
+            "<p>Veja também: <a href=\"{}\"><code>{}</code></a></p>\n",
+            html_escape(file),
+            html_escape(name)
+        ));
+    }
+
+    format!(
+        "<!DOCTYPE html>
+<html lang=\"pt-BR\">
+<head>
+<meta charset=\"utf-8\">
+<title>{title}</title>
+<style>
+body {{ font-family: sans-serif; max-width: 960px; margin: 2em auto; padding: 0 1em; }}
+table {{ border-collapse: collapse; width: 100%; }}
+th, td {{ border: 1px solid #ccc; padding: 0.4em; text-align: left; vertical-align: top; }}
+pre {{ background: #f5f5f5; padding: 0.8em; overflow-x: auto; }}
+</style>
+</head>
+<body>
+{body}</body>
+</html>
+",
+        title = html_escape(&model.title)
+    )
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,83 @@
+use super::*;
+use crate::command::PositionalArg;
+use crate::flag::{FlagType, FlagValue};
+use crate::test_utils::TempDir;
+
+fn test_app() -> App {
+    App::new("app", "1.0.0")
+        .description("Aplicação de teste")
+        .add_global_flag(Flag::new("verbose", FlagType::Bool).short('v').description("Modo verboso"))
+        .add_command(
+            Command::new("calc")
+                .description("Calculadora")
+                .add_subcommand(
+                    Command::new("add")
+                        .description("Soma números")
+                        .add_flag(
+                            Flag::new("format", FlagType::String)
+                                .description("Formato | saída")
+                                .possible_values(vec!["json".to_string(), "text".to_string()])
+                                .default_value(FlagValue::String("text".to_string())),
+                        )
+                        .add_positional_arg(PositionalArg::new("numbers").description("Números"))
+                        .example("app calc add 1 2", "Soma 1 e 2"),
+                ),
+        )
+        .add_command(Command::new("internal").hidden(true))
+}
+
+#[test]
+fn test_one_page_per_visible_command() {
+    let names: Vec<String> = render(&test_app(), DocFormat::Markdown)
+        .into_iter()
+        .map(|page| page.file_name)
+        .collect();
+    assert_eq!(names, vec!["app.md", "app-calc.md", "app-calc-add.md"]);
+}
+
+#[test]
+fn test_markdown_page() {
+    let pages = render(&test_app(), DocFormat::Markdown);
+
+    let root = &pages[0].content;
+    assert!(root.starts_with("# app\n\nVersão 1.0.0\n\nAplicação de teste\n"));
+    assert!(root.contains("| [`calc`](app-calc.md) | Calculadora |"));
+
+    let add = &pages[2].content;
+    assert!(add.contains("## Uso\n\n```text\napp calc add [OPÇÕES] <numbers>\n```"));
+    assert!(add.contains("| `numbers` | sim | Números |"));
+    assert!(add.contains(
+        "| `--format` | string | não | text | Formato \\| saída Valores possíveis: json, text. |"
+    ));
+    assert!(add.contains("## Opções globais\n\n| Flag | Tipo | Obrigatória | Padrão | Descrição |"));
+    assert!(add.contains("| `-v, --verbose` |  | não |  | Modo verboso |"));
+    assert!(add.contains("## Exemplos\n\nSoma 1 e 2\n\n```sh\napp calc add 1 2\n```"));
+    assert!(add.ends_with("Veja também: [`app calc`](app-calc.md)\n"));
+}
+
+#[test]
+fn test_html_page() {
+    let pages = render(&test_app(), DocFormat::Html);
+    assert_eq!(pages[1].file_name, "app-calc.html");
+
+    let add = &pages[2].content;
+    assert!(add.starts_with("<!DOCTYPE html>"));
+    assert!(add.contains("<title>app calc add</title>"));
+    assert!(add.contains("<pre><code>app calc add [OPÇÕES] &lt;numbers&gt;</code></pre>"));
+    assert!(add.contains("<td><code>--format</code></td><td>string</td>"));
+    assert!(add.contains("<a href=\"app-calc.html\"><code>app calc</code></a>"));
+}
+
+#[test]
+fn test_output_is_stable() {
+    assert_eq!(render(&test_app(), DocFormat::Markdown), render(&test_app(), DocFormat::Markdown));
+}
+
+#[test]
+fn test_write_to_dir() {
+    let dir = TempDir::new("docs");
+
+    let written = test_app().write_docs(dir.path(), DocFormat::Html).unwrap();
+    assert_eq!(written.len(), 3);
+    assert!(dir.path().join("app-calc-add.html").is_file());
+}
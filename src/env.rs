@@ -0,0 +1,38 @@
+use std::collections::HashMap;
+
+/// Origem das variáveis de ambiente lidas pelas flags configuradas com `Flag::env`.
+/// `Map` permite injetar valores fixos, útil em testes
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum EnvSource {
+    /// Ambiente do processo (`std::env::var`)
+    #[default]
+    Process,
+    /// Valores fixos, sem consultar o ambiente do processo
+    Map(HashMap<String, String>),
+}
+
+impl EnvSource {
+    pub fn from_pairs<I, K, V>(pairs: I) -> Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: Into<String>,
+        V: Into<String>,
+    {
+        EnvSource::Map(
+            pairs
+                .into_iter()
+                .map(|(key, value)| (key.into(), value.into()))
+                .collect(),
+        )
+    }
+
+    /// Retorna o valor da variável, tratando variáveis vazias como ausentes
+    pub fn get(&self, name: &str) -> Option<String> {
+        let value = match self {
+            EnvSource::Process => std::env::var(name).ok(),
+            EnvSource::Map(values) => values.get(name).cloned(),
+        };
+
+        value.filter(|value| !value.is_empty())
+    }
+}
@@ -1,10 +1,11 @@
+use crate::command::GroupKind;
 use thiserror::Error;
 pub type Result<T> = std::result::Result<T, AppError>;
 
 #[derive(Error, Debug, Clone, PartialEq)]
 pub enum AppError {
     #[error("Comando não encontrado: {command}")]
-    CommandNotFound { command: String },
+    CommandNotFound { command: String, suggestions: Vec<String> },
 
     #[error("Subcomando não encontrado: {subcommand}")]
     SubcommandNotFound { subcommand: String },
@@ -13,14 +14,50 @@ pub enum AppError {
     RequiredFlagNotProvided { flag: String },
 
     #[error("Flag desconhecida: --{flag}")]
-    UnknownFlag { flag: String },
+    UnknownFlag { flag: String, suggestions: Vec<String> },
+
+    #[error("Flag desconhecida: -{flag} (posição {position} em -{cluster})")]
+    UnknownFlagInCluster { flag: char, cluster: String, position: usize },
 
     #[error("Valor inválido para flag: --{flag}: {value}. Esperado: {expected}")]
-    InvalidFlagValue { flag: String, value: String, expected: String },
+    InvalidFlagValue { flag: String, value: String, expected: String, suggestions: Vec<String> },
+
+    #[error("Valor inválido para o argumento <{arg}>: {value}. Esperado: {expected}")]
+    InvalidArgumentValue { arg: String, value: String, expected: String, suggestions: Vec<String> },
+
+    #[error("Valor inválido na variável de ambiente {var} (flag --{flag}): {message}")]
+    InvalidEnvValue { flag: String, var: String, message: String },
+
+    #[error("Erro no arquivo de configuração {path}: {message}")]
+    ConfigFileError { path: String, message: String },
+
+    #[error("Valor inválido no arquivo de configuração {path}, chave '{key}': {message}")]
+    InvalidConfigValue { path: String, key: String, message: String },
+
+    #[error("A flag --{flag} não pode ser usada junto com --{other}")]
+    ConflictingFlags { flag: String, other: String },
+
+    #[error("A flag --{flag} requer --{required}")]
+    MissingRequiredFlag { flag: String, required: String },
+
+    #[error("Flag obrigatória não fornecida: --{flag} (ou --{})", .alternatives.join(", --"))]
+    RequiredUnlessNotProvided { flag: String, alternatives: Vec<String> },
+
+    #[error("Grupo {group}: informe {} --{}", .kind.description(), .flags.join(", --"))]
+    GroupNotSatisfied { group: String, kind: GroupKind, flags: Vec<String> },
+
+    #[error("As flags --{flag} e --{other} do grupo {group} não podem ser usadas juntas")]
+    GroupConflict { group: String, flag: String, other: String },
 
     #[error("Flag --{flag} requer um valor")]
     FlagValueMissing { flag: String },
 
+    #[error("Número de valores inválido para --{flag}: esperado {expected}, recebido {received}")]
+    InvalidValueCount { flag: String, expected: String, received: usize },
+
+    #[error("Flag --{flag} não aceita valor, recebido: {value}")]
+    FlagDoesNotTakeValue { flag: String, value: String },
+
     #[error("Muitos argumentos posicionais fornecidos")]
     TooManyArguments,
 
@@ -30,15 +67,47 @@ pub enum AppError {
     #[error("Erro de I/O: {0}")]
     IoError(String),
 
+    #[error("Flag --{flag} contém um valor do tipo {actual}, pedido como {expected}")]
+    ValueTypeMismatch { flag: String, expected: String, actual: String },
+
+    /// Falha de uma ação de comando com código de saída próprio (`App::dispatch`)
+    #[error("{message}")]
+    CommandFailed { message: String, code: i32 },
+
     #[error("Erro de parsing: {message}")]
     ParseError { message: String },
 
+    /// A ajuda foi pedida com `--help` em vez de um valor (`Parser::try_parse_from`)
+    #[error("Ajuda solicitada")]
+    HelpRequested,
+
     #[error("Aplicação nâo configurada corretamente: {message}")]
     ConfigurationError {message: String}
 }
 
+impl AppError {
+    /// Sugestões de correção ("você quis dizer ...?") associadas ao erro
+    pub fn suggestions(&self) -> &[String] {
+        match self {
+            AppError::CommandNotFound { suggestions, .. }
+            | AppError::UnknownFlag { suggestions, .. }
+            | AppError::InvalidFlagValue { suggestions, .. }
+            | AppError::InvalidArgumentValue { suggestions, .. } => suggestions,
+            _ => &[],
+        }
+    }
+}
+
 impl From<std::io::Error> for AppError {
     fn from(error: std::io::Error) -> Self {
         AppError::IoError(error.to_string())
     }
-}
\ No newline at end of file
+}
+
+impl From<crate::de::Error> for AppError {
+    fn from(error: crate::de::Error) -> Self {
+        AppError::ParseError {
+            message: error.to_string(),
+        }
+    }
+}
@@ -1,7 +1,14 @@
 use crate::AppError;
+use crate::completions::{Candidate, ValueCompleter};
+use crate::suggestions::did_you_mean;
+use crate::value_parser::{AnyValueParser, ValueParser};
+use serde::Serialize;
+use std::fmt;
+use std::ops::{Bound, RangeBounds};
 
 /// Tipos de valores que uma flag pode aceitar
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum FlagType {
     /// Flag booleana (--verbose, --help)
     Bool,
@@ -11,10 +18,12 @@ pub enum FlagType {
     Integer,
     /// Numero real (--price 10.5)
     Float,
-    /// Lista de strings (--tags "tag1" "tag2")
+    /// Lista de strings (--tags "tag1" "tag2" ou --tags tag1,tag2)
     StringList,
     /// Lista de inteiros (--ids 1 2 3)
     IntegerList,
+    /// Contador de ocorrências (-vvv), guardado como `FlagValue::Integer`
+    Count,
 }
 
 impl FlagType {
@@ -27,11 +36,70 @@ impl FlagType {
             FlagType::Integer => "integer",
             FlagType::StringList => "string list",
             FlagType::IntegerList => "integer list",
+            FlagType::Count => "count",
         }
     }
+
+    /// Se a flag recebe um valor na linha de comando; `Bool` e `Count` não recebem
+    pub fn takes_value(&self) -> bool {
+        !matches!(self, FlagType::Bool | FlagType::Count)
+    }
+
+    /// Se a flag recebe vários valores (`StringList` e `IntegerList`)
+    pub fn is_list(&self) -> bool {
+        matches!(self, FlagType::StringList | FlagType::IntegerList)
+    }
+}
+
+/// Quantidade de valores aceita em cada ocorrência de uma flag de lista,
+/// definida com `Flag::num_values`. Sem limite superior quando `max` é `None`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct ValueRange {
+    pub min: usize,
+    pub max: Option<usize>,
+}
+
+impl ValueRange {
+    pub fn new(range: impl RangeBounds<usize>) -> Self {
+        let min = match range.start_bound() {
+            Bound::Included(min) => *min,
+            Bound::Excluded(min) => min + 1,
+            Bound::Unbounded => 0,
+        };
+        let max = match range.end_bound() {
+            Bound::Included(max) => Some(*max),
+            Bound::Excluded(max) => Some(max.saturating_sub(1)),
+            Bound::Unbounded => None,
+        };
+
+        Self { min, max }
+    }
+
+    pub fn contains(&self, count: usize) -> bool {
+        count >= self.min && self.max.is_none_or(|max| count <= max)
+    }
+}
+
+/// Padrão das flags de lista: um ou mais valores
+impl Default for ValueRange {
+    fn default() -> Self {
+        Self { min: 1, max: None }
+    }
 }
 
-#[derive(Debug, Clone, PartialEq)]
+impl fmt::Display for ValueRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.max {
+            Some(max) if max == self.min => write!(f, "exatamente {}", max),
+            Some(max) if self.min == 0 => write!(f, "no máximo {}", max),
+            Some(max) => write!(f, "de {} a {}", self.min, max),
+            None => write!(f, "ao menos {}", self.min),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(untagged)]
 pub enum FlagValue {
     Bool(bool),
     String(String),
@@ -85,19 +153,57 @@ impl FlagValue {
     }
 }
 
-#[derive(Debug, Clone)]
+impl fmt::Display for FlagValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FlagValue::Bool(b) => write!(f, "{}", b),
+            FlagValue::String(s) => write!(f, "{}", s),
+            FlagValue::Float(n) => write!(f, "{}", n),
+            FlagValue::Integer(n) => write!(f, "{}", n),
+            FlagValue::StringList(list) => write!(f, "{}", list.join(", ")),
+            FlagValue::IntegerList(list) => {
+                let items: Vec<String> = list.iter().map(|n| n.to_string()).collect();
+                write!(f, "{}", items.join(", "))
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
 pub struct Flag {
+    /// Omitido na especificação, onde as flags são indexadas pelo nome
+    #[serde(skip)]
     pub name: String,
     pub short: Option<char>,
+    #[serde(rename = "type")]
     pub flag_type: FlagType,
     pub description: String,
     pub required: bool,
+    #[serde(rename = "default")]
     pub default_value: Option<FlagValue>,
+    #[serde(rename = "default_missing")]
+    pub default_missing_value: Option<FlagValue>,
     pub possible_values: Option<Vec<String>>,
+    pub allow_hyphen_values: bool,
+    pub num_values: Option<ValueRange>,
+    pub value_delimiter: Option<char>,
+    pub global: bool,
+    pub negatable: bool,
+    pub env: Option<String>,
+    pub conflicts_with: Vec<String>,
+    pub requires: Vec<String>,
+    pub required_unless: Vec<String>,
+    pub decrements: Option<String>,
+    #[serde(skip)]
+    pub value_completer: Option<ValueCompleter>,
+    #[serde(skip)]
+    pub value_parser: Option<AnyValueParser>,
 }
 
 impl Flag {
     pub fn new(name: impl Into<String>, flag_type: FlagType) -> Self {
+        let value_delimiter = flag_type.is_list().then_some(',');
+
         Self {
             name: name.into(),
             short: None,
@@ -105,7 +211,20 @@ impl Flag {
             description: String::new(),
             required: false,
             default_value: None,
+            default_missing_value: None,
             possible_values: None,
+            allow_hyphen_values: false,
+            num_values: None,
+            value_delimiter,
+            global: false,
+            negatable: false,
+            env: None,
+            conflicts_with: Vec::new(),
+            requires: Vec::new(),
+            required_unless: Vec::new(),
+            decrements: None,
+            value_completer: None,
+            value_parser: None,
         }
     }
 
@@ -129,26 +248,153 @@ impl Flag {
         self
     }
 
+    /// Permite informar a flag sem valor (`--color`), usando `default_missing`. O
+    /// valor vem de `--color=valor` ou do próximo argumento, se ele não for uma flag
+    /// nem um subcomando. Diferente de `default_value`, usado quando a flag não aparece
+    pub fn optional_value(mut self, default_missing: FlagValue) -> Self {
+        self.default_missing_value = Some(default_missing);
+        self
+    }
+
     pub fn possible_values(mut self, possible_values: Vec<String>) -> Self {
         self.possible_values = Some(possible_values);
         self
     }
 
+    /// Permite que o valor da flag comece com `-` (ex: `--offset -5`)
+    pub fn allow_hyphen_values(mut self, allow_hyphen_values: bool) -> Self {
+        self.allow_hyphen_values = allow_hyphen_values;
+        self
+    }
+
+    /// Quantos valores cada ocorrência de uma flag de lista aceita, ex: `1..=3` ou
+    /// `2..`. Sem isso, uma ocorrência consome um ou mais valores, até a próxima
+    /// flag ou subcomando
+    pub fn num_values(mut self, range: impl RangeBounds<usize>) -> Self {
+        self.num_values = Some(ValueRange::new(range));
+        self
+    }
+
+    /// Caractere que separa vários valores em um mesmo argumento (`--tags a,b`).
+    /// Flags de lista usam `,` por padrão; `None` desativa a separação
+    pub fn value_delimiter(mut self, delimiter: Option<char>) -> Self {
+        self.value_delimiter = delimiter;
+        self
+    }
+
+    /// Valores aceitos por ocorrência, `num_values` ou o padrão de `ValueRange`
+    pub fn value_range(&self) -> ValueRange {
+        self.num_values.unwrap_or_default()
+    }
+
+    /// Divide `value` em `value_delimiter`, se houver
+    pub fn split_values(&self, value: &str) -> Vec<String> {
+        match self.value_delimiter {
+            Some(delimiter) => value.split(delimiter).map(str::to_string).collect(),
+            None => vec![value.to_string()],
+        }
+    }
+
+    /// Torna a flag aceita em qualquer subcomando abaixo do comando que a define
+    pub fn global(mut self, global: bool) -> Self {
+        self.global = global;
+        self
+    }
+
+    /// Aceita também `--no-<nome>` em flags `Bool`, que define o valor como `false`.
+    /// Quando as duas formas aparecem, vale a última
+    pub fn negatable(mut self, negatable: bool) -> Self {
+        self.negatable = negatable;
+        self
+    }
+
+    /// Nome longo da forma negada (`no-<nome>`), se a flag for negável
+    pub fn negated_name(&self) -> Option<String> {
+        (self.negatable && matches!(self.flag_type, FlagType::Bool)).then(|| format!("no-{}", self.name))
+    }
+
+    /// Nome longo exibido na ajuda e na documentação: `[no-]color` em flags negáveis
+    pub fn display_name(&self) -> String {
+        match self.negated_name() {
+            Some(_) => format!("[no-]{}", self.name),
+            None => self.name.clone(),
+        }
+    }
+
+    /// Variável de ambiente lida quando a flag não é informada na linha de comando.
+    /// Precedência: linha de comando > variável de ambiente > valor padrão
+    pub fn env(mut self, env: impl Into<String>) -> Self {
+        self.env = Some(env.into());
+        self
+    }
+
+    /// Impede que esta flag e `flag` (do mesmo comando) sejam informadas juntas.
+    /// Valores padrão não contam como informados
+    pub fn conflicts_with(mut self, flag: impl Into<String>) -> Self {
+        self.conflicts_with.push(flag.into());
+        self
+    }
+
+    /// Exige que `flag` (do mesmo comando) tenha valor quando esta for informada
+    pub fn requires(mut self, flag: impl Into<String>) -> Self {
+        self.requires.push(flag.into());
+        self
+    }
+
+    /// Torna a flag obrigatória, a menos que `flag` (do mesmo comando) tenha valor.
+    /// Chamado mais de uma vez, basta uma delas
+    pub fn required_unless(mut self, flag: impl Into<String>) -> Self {
+        self.required_unless.push(flag.into());
+        self
+    }
+
+    /// Faz de uma flag `Count` o contador negativo de `flag` (outra flag `Count` do
+    /// mesmo comando): cada ocorrência desta subtrai um do valor de `flag`, ex: `-q`
+    /// compensando `-v`
+    pub fn decrements(mut self, flag: impl Into<String>) -> Self {
+        self.decrements = Some(flag.into());
+        self
+    }
+
+    /// Função chamada pelo autocompletar dinâmico (`App::complete`) para sugerir
+    /// valores a partir do que já foi digitado, ex: nomes de branches
+    pub fn value_completer<F>(mut self, completer: F) -> Self
+    where
+        F: Fn(&str) -> Vec<Candidate> + Send + Sync + 'static,
+    {
+        self.value_completer = Some(ValueCompleter::new(completer));
+        self
+    }
+
+    /// Converte cada valor da flag em um tipo próprio, lido com `ParsedArgs::get` e
+    /// `ParsedArgs::get_many`. O valor continua sendo validado pelo `FlagType`
+    pub fn value_parser<P: ValueParser>(mut self, parser: P) -> Self {
+        self.value_parser = Some(AnyValueParser::new(parser));
+        self
+    }
+
     pub fn parse_value(&self, value: &str) -> Result<FlagValue, AppError> {
-        println!("flag: {} flag_value: {:?}", self.name, value);
         match self.flag_type {
-            FlagType::Bool => Ok(FlagValue::Bool(
-                value.parse().unwrap_or_else(|_| !value.is_empty()),
-            )),
+            FlagType::Bool => match value.to_ascii_lowercase().as_str() {
+                "true" | "yes" | "1" => Ok(FlagValue::Bool(true)),
+                "false" | "no" | "0" => Ok(FlagValue::Bool(false)),
+                _ => Err(AppError::InvalidFlagValue {
+                    flag: self.name.clone(),
+                    value: value.to_string(),
+                    expected: "true, false, yes, no, 1 or 0".to_string(),
+                    suggestions: Vec::new(),
+                }),
+            },
             FlagType::String => {
                 self.validate_possible_values(value)?;
                 Ok(FlagValue::String(value.to_string()))
             }
-            FlagType::Integer => {
+            FlagType::Integer | FlagType::Count => {
                 let parsed: i64 = value.parse().map_err(|_| AppError::InvalidFlagValue {
                     flag: self.name.clone(),
                     value: value.to_string(),
                     expected: "integer".to_string(),
+                    suggestions: Vec::new(),
                 })?;
                 Ok(FlagValue::Integer(parsed))
             }
@@ -157,23 +403,12 @@ impl Flag {
                     flag: self.name.clone(),
                     value: value.to_string(),
                     expected: "float".to_string(),
+                    suggestions: Vec::new(),
                 })?;
                 Ok(FlagValue::Float(parsed))
             }
-            FlagType::StringList => Ok(FlagValue::StringList(vec![value.to_string()])),
-            FlagType::IntegerList => {
-                // Divide string na virgula
-                let parsed: Result<Vec<i64>, _> =
-                    value.split(',').map(|s| s.trim().parse::<i64>()).collect();
-
-                match parsed {
-                    Ok(list) => Ok(FlagValue::IntegerList(list)),
-                    Err(_) => Err(AppError::InvalidFlagValue {
-                        flag: self.name.clone(),
-                        value: value.to_string(),
-                        expected: "comma-separated integers".to_string(),
-                    }),
-                }
+            FlagType::StringList | FlagType::IntegerList => {
+                self.parse_values(&self.split_values(value))
             }
         }
     }
@@ -189,10 +424,11 @@ impl Flag {
             FlagType::IntegerList => {
                 let mut parse_values = Vec::new();
                 for value in values {
-                    let parsed: i64 = value.parse().map_err(|_| AppError::InvalidFlagValue {
+                    let parsed: i64 = value.trim().parse().map_err(|_| AppError::InvalidFlagValue {
                         flag: self.name.clone(),
                         value: value.clone(),
                         expected: "integer".to_string(),
+                        suggestions: Vec::new(),
                     })?;
                     parse_values.push(parsed);
                 }
@@ -204,6 +440,7 @@ impl Flag {
                         flag: self.name.clone(),
                         value: values.join(", "),
                         expected: format!("single {}", self.flag_type.description()),
+                        suggestions: Vec::new(),
                     });
                 }
                 self.parse_value(&values[0])
@@ -211,6 +448,25 @@ impl Flag {
         }
     }
 
+    /// Indica se `value` é do tipo da flag e está entre os valores possíveis
+    pub(crate) fn accepts_value(&self, value: &FlagValue) -> bool {
+        let possible = |value: &String| {
+            self.possible_values
+                .as_ref()
+                .is_none_or(|possible| possible.contains(value))
+        };
+
+        match (&self.flag_type, value) {
+            (FlagType::Bool, FlagValue::Bool(_))
+            | (FlagType::Integer | FlagType::Count, FlagValue::Integer(_))
+            | (FlagType::Float, FlagValue::Float(_))
+            | (FlagType::IntegerList, FlagValue::IntegerList(_)) => true,
+            (FlagType::String, FlagValue::String(value)) => possible(value),
+            (FlagType::StringList, FlagValue::StringList(values)) => values.iter().all(possible),
+            _ => false,
+        }
+    }
+
     fn validate_possible_values(&self, value: &str) -> Result<(), AppError> {
         if let Some(ref possible) = self.possible_values {
             if !possible.contains(&value.to_string()) {
@@ -218,6 +474,7 @@ impl Flag {
                     flag: self.name.clone(),
                     value: value.to_string(),
                     expected: format!("one of {:?}", possible.join(", ")),
+                    suggestions: did_you_mean(value, possible.iter().map(String::as_str)),
                 });
             }
         }
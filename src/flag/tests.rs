@@ -10,6 +10,9 @@ fn test_new_flag() {
     assert!(!flag.required);
     assert!(flag.default_value.is_none());
     assert!(flag.possible_values.is_none());
+    assert!(!flag.allow_hyphen_values);
+    assert!(!flag.global);
+    assert!(flag.env.is_none());
 }
 
 #[test]
@@ -52,11 +55,22 @@ fn test_parse_bool_value_false() {
 }
 
 #[test]
-fn test_parse_bool_value_empty() {
+fn test_parse_bool_value_aliases() {
     let flag = Flag::new("verbose", FlagType::Bool);
-    let result = flag.parse_value("");
-    assert!(result.is_ok());
-    assert_eq!(result.unwrap(), FlagValue::Bool(false));
+    for (value, expected) in [("yes", true), ("1", true), ("TRUE", true), ("no", false), ("0", false)] {
+        assert_eq!(flag.parse_value(value).unwrap(), FlagValue::Bool(expected));
+    }
+}
+
+#[test]
+fn test_parse_bool_value_invalid() {
+    let flag = Flag::new("verbose", FlagType::Bool);
+    for value in ["", "sim", "2"] {
+        assert!(matches!(
+            flag.parse_value(value),
+            Err(AppError::InvalidFlagValue { .. })
+        ));
+    }
 }
 
 #[test]
@@ -215,3 +229,46 @@ fn test_parse_values_single_value_type_with_multiple_values() {
         AppError::InvalidFlagValue { .. }
     ));
 }
+
+#[test]
+fn test_invalid_possible_value_suggestions() {
+    let flag = Flag::new("output", FlagType::String)
+        .possible_values(vec!["json".to_string(), "table".to_string(), "yaml".to_string()]);
+
+    let result = flag.parse_value("jsn");
+    assert_eq!(result.unwrap_err().suggestions(), &["json".to_string()]);
+
+    let result = flag.parse_value("xml");
+    assert!(result.unwrap_err().suggestions().is_empty());
+}
+
+#[test]
+fn test_value_range() {
+    assert_eq!(ValueRange::new(1..=3), ValueRange { min: 1, max: Some(3) });
+    assert_eq!(ValueRange::new(2..), ValueRange { min: 2, max: None });
+    assert_eq!(ValueRange::new(..4), ValueRange { min: 0, max: Some(3) });
+    assert!(ValueRange::default().contains(5));
+    assert!(!ValueRange::default().contains(0));
+
+    assert_eq!(ValueRange::new(2..=2).to_string(), "exatamente 2");
+    assert_eq!(ValueRange::new(1..=3).to_string(), "de 1 a 3");
+    assert_eq!(ValueRange::new(..=3).to_string(), "no máximo 3");
+    assert_eq!(ValueRange::new(1..).to_string(), "ao menos 1");
+}
+
+#[test]
+fn test_list_value_delimiter() {
+    let flag = Flag::new("tags", FlagType::StringList);
+    assert_eq!(flag.value_delimiter, Some(','));
+    assert_eq!(
+        flag.parse_value("a,b").unwrap(),
+        FlagValue::StringList(vec!["a".to_string(), "b".to_string()])
+    );
+
+    let flag = Flag::new("tags", FlagType::StringList).value_delimiter(None);
+    assert_eq!(flag.parse_value("a,b").unwrap(), FlagValue::StringList(vec!["a,b".to_string()]));
+
+    let flag = Flag::new("ids", FlagType::IntegerList).value_delimiter(Some(':'));
+    assert_eq!(flag.parse_value("1:2").unwrap(), FlagValue::IntegerList(vec![1, 2]));
+    assert_eq!(Flag::new("name", FlagType::String).value_delimiter, None);
+}
@@ -1,12 +1,35 @@
+pub mod action;
 pub mod cli;
 pub mod command;
+pub mod completions;
+pub mod config;
+pub mod de;
+pub mod derive;
+pub mod docs;
 pub mod parser;
+pub mod spec;
 pub mod ui;
 pub mod errors;
+pub mod env;
 pub mod flag;
+pub mod log_level;
+pub mod man;
+pub mod value_parser;
+mod suggestions;
+#[cfg(test)]
+mod test_utils;
 
+pub use action::Context;
 pub use cli::App;
 pub use command::Command;
+pub use completions::{Candidate, Shell};
 pub use flag::{Flag, FlagType};
-pub use parser::ParsedArgs;
-pub use errors::{AppError, Result};
\ No newline at end of file
+pub use parser::{ParsedArgs, ValueSource};
+pub use errors::{AppError, Result};
+pub use env::EnvSource;
+pub use log_level::LogLevel;
+pub use value_parser::ValueParser;
+pub use derive::{Args, FlagArg, Parser, Subcommand, ValueEnum};
+
+#[cfg(feature = "derive")]
+pub use cliparser_derive::{Parser, Subcommand, ValueEnum};
\ No newline at end of file
@@ -0,0 +1,72 @@
+//! Níveis de log derivados de contadores de verbosidade (`-vvv`, `-q`), lidos com
+//! `ParsedArgs::log_level`
+
+use std::fmt;
+use std::str::FromStr;
+
+/// Nível de log, do mais silencioso ao mais detalhado
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LogLevel {
+    Off,
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl LogLevel {
+    /// Todos os níveis, em ordem crescente de detalhe
+    pub const ALL: [LogLevel; 6] = [
+        LogLevel::Off,
+        LogLevel::Error,
+        LogLevel::Warn,
+        LogLevel::Info,
+        LogLevel::Debug,
+        LogLevel::Trace,
+    ];
+
+    /// Nível `offset` posições acima (positivo) ou abaixo (negativo) deste,
+    /// limitado a `Off` e `Trace`
+    pub fn offset(self, offset: i64) -> LogLevel {
+        let index = (self as i64).saturating_add(offset).clamp(0, Self::ALL.len() as i64 - 1);
+        Self::ALL[index as usize]
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LogLevel::Off => "off",
+            LogLevel::Error => "error",
+            LogLevel::Warn => "warn",
+            LogLevel::Info => "info",
+            LogLevel::Debug => "debug",
+            LogLevel::Trace => "trace",
+        }
+    }
+}
+
+impl fmt::Display for LogLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for LogLevel {
+    type Err = String;
+
+    /// Aceita os nomes de `as_str`, sem diferenciar maiúsculas, e `warning`
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = value.to_ascii_lowercase();
+        if value == "warning" {
+            return Ok(LogLevel::Warn);
+        }
+
+        Self::ALL
+            .into_iter()
+            .find(|level| level.as_str() == value)
+            .ok_or_else(|| "um de off, error, warn, info, debug ou trace".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests;
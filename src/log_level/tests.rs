@@ -0,0 +1,19 @@
+use super::*;
+
+#[test]
+fn test_offset_clamps() {
+    assert_eq!(LogLevel::Warn.offset(2), LogLevel::Debug);
+    assert_eq!(LogLevel::Warn.offset(10), LogLevel::Trace);
+    assert_eq!(LogLevel::Warn.offset(-1), LogLevel::Error);
+    assert_eq!(LogLevel::Warn.offset(-10), LogLevel::Off);
+    assert_eq!(LogLevel::Warn.offset(i64::MAX), LogLevel::Trace);
+    assert_eq!(LogLevel::Warn.offset(i64::MIN), LogLevel::Off);
+}
+
+#[test]
+fn test_from_str_and_display() {
+    assert_eq!("DEBUG".parse::<LogLevel>(), Ok(LogLevel::Debug));
+    assert_eq!("warning".parse::<LogLevel>(), Ok(LogLevel::Warn));
+    assert!("verbose".parse::<LogLevel>().is_err());
+    assert_eq!(LogLevel::Trace.to_string(), "trace");
+}
@@ -0,0 +1,226 @@
+use crate::ui::ColoredUI;
+use crate::{App, AppError, Command, Flag};
+use std::path::{Path, PathBuf};
+
+/// Página de manual (`man(7)`, seção 1) de um comando
+#[derive(Debug, Clone, PartialEq)]
+pub struct ManPage {
+    /// Nome da página, ex: `app-calc-add`
+    pub name: String,
+    /// Conteúdo em roff
+    pub content: String,
+}
+
+impl ManPage {
+    pub fn file_name(&self) -> String {
+        format!("{}.1", self.name)
+    }
+}
+
+/// Gera uma página para a aplicação e uma para cada subcomando visível
+pub fn render(app: &App) -> Vec<ManPage> {
+    let mut pages = Vec::new();
+    render_tree(app, &app.root_command, &mut Vec::new(), &mut pages);
+    pages
+}
+
+fn render_tree<'a>(
+    app: &App,
+    command: &'a Command,
+    path: &mut Vec<&'a str>,
+    pages: &mut Vec<ManPage>,
+) {
+    pages.push(render_command(app, path, command));
+
+    for subcommand in command.get_visible_subcommands() {
+        path.push(&subcommand.name);
+        render_tree(app, subcommand, path, pages);
+        path.pop();
+    }
+}
+
+/// Escreve as páginas de `render` em `dir` (criado se necessário) como `<nome>.1`
+/// e retorna os caminhos gerados
+pub fn write_to_dir(app: &App, dir: impl AsRef<Path>) -> Result<Vec<PathBuf>, AppError> {
+    let dir = dir.as_ref();
+    std::fs::create_dir_all(dir)?;
+
+    let mut written = Vec::new();
+    for page in render(app) {
+        let path = dir.join(page.file_name());
+        std::fs::write(&path, page.content)?;
+        written.push(path);
+    }
+
+    Ok(written)
+}
+
+/// Nome da página do comando em `path`, ex: `app-calc-add`
+pub(crate) fn page_name(app: &App, path: &[&str]) -> String {
+    std::iter::once(app.name.as_str())
+        .chain(path.iter().copied())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Escapa texto para roff: barras invertidas, hífens e linhas iniciadas por `.` ou `'`
+fn escape(text: &str) -> String {
+    let escaped = text.replace('\\', "\\e").replace('-', "\\-");
+
+    escaped
+        .lines()
+        .map(|line| {
+            if line.starts_with('.') || line.starts_with('\'') {
+                format!("\\&{}", line)
+            } else {
+                line.to_string()
+            }
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn render_command(app: &App, path: &[&str], command: &Command) -> ManPage {
+    let name = page_name(app, path);
+    let description = if path.is_empty() {
+        &app.description
+    } else {
+        &command.description
+    };
+
+    let mut page = format!(
+        ".TH {} 1 \"\" \"{} {}\"\n",
+        escape(&name.to_uppercase()),
+        escape(&app.name),
+        escape(&app.version)
+    );
+
+    page.push_str(".SH NAME\n");
+    if description.is_empty() {
+        page.push_str(&format!("{}\n", escape(&name)));
+    } else {
+        page.push_str(&format!("{} \\- {}\n", escape(&name), escape(first_line(description))));
+    }
+
+    // A sinopse reaproveita a linha de uso da ajuda, com o comando em negrito
+    let invocation = std::iter::once(app.name.as_str())
+        .chain(path.iter().copied())
+        .collect::<Vec<_>>()
+        .join(" ");
+    let usage = ColoredUI::format_usage(&app.name, path, command);
+    let rest = usage.strip_prefix(&invocation).unwrap_or_default();
+
+    page.push_str(".SH SYNOPSIS\n");
+    page.push_str(&format!("\\fB{}\\fR{}\n", escape(&invocation), escape(rest)));
+
+    if !description.is_empty() {
+        page.push_str(".SH DESCRIPTION\n");
+        page.push_str(&format!("{}\n", escape(description)));
+    }
+
+    if command.has_flags() {
+        page.push_str(".SH OPTIONS\n");
+        for flag in command.get_flags_sorted() {
+            page.push_str(&render_flag(flag));
+        }
+    }
+
+    let global_flags = app.root_command.inherited_global_flags(path);
+    if !global_flags.is_empty() {
+        page.push_str(".SH GLOBAL OPTIONS\n");
+        for flag in global_flags {
+            page.push_str(&render_flag(flag));
+        }
+    }
+
+    if command.has_positional_args() {
+        page.push_str(".SH ARGUMENTS\n");
+        for arg in &command.positional_args {
+            let optional = if arg.required { "" } else { " (opcional)" };
+            page.push_str(&format!(".TP\n\\fI{}\\fR{}\n", escape(&arg.name), optional));
+            if !arg.description.is_empty() {
+                page.push_str(&format!("{}\n", escape(&arg.description)));
+            }
+        }
+    }
+
+    if !command.examples.is_empty() {
+        page.push_str(".SH EXAMPLES\n");
+        for example in &command.examples {
+            page.push_str(&format!(".TP\n\\fB{}\\fR\n", escape(&example.command)));
+            if !example.description.is_empty() {
+                page.push_str(&format!("{}\n", escape(&example.description)));
+            }
+        }
+    }
+
+    let mut see_also: Vec<String> = Vec::new();
+    if let Some((_, parent)) = path.split_last() {
+        see_also.push(page_name(app, parent));
+    }
+    for subcommand in command.get_visible_subcommands() {
+        let mut child = path.to_vec();
+        child.push(&subcommand.name);
+        see_also.push(page_name(app, &child));
+    }
+
+    if !see_also.is_empty() {
+        page.push_str(".SH SEE ALSO\n");
+        let references: Vec<String> = see_also
+            .iter()
+            .map(|name| format!(".BR {} (1)", escape(name)))
+            .collect();
+        page.push_str(&references.join(",\n"));
+        page.push('\n');
+    }
+
+    ManPage {
+        name,
+        content: page,
+    }
+}
+
+fn render_flag(flag: &Flag) -> String {
+    let mut names = Vec::new();
+    if let Some(short) = flag.short {
+        names.push(format!("\\fB\\-{}\\fR", short));
+    }
+    names.push(format!("\\fB\\-\\-{}\\fR", escape(&flag.display_name())));
+
+    let type_hint = if !flag.flag_type.takes_value() {
+        String::new()
+    } else if flag.default_missing_value.is_some() {
+        format!("[=\\fI<{}>\\fR]", flag.flag_type.description())
+    } else {
+        format!(" \\fI<{}>\\fR", flag.flag_type.description())
+    };
+
+    let mut entry = format!(".TP\n{}{}\n", names.join(", "), type_hint);
+
+    let required = if flag.required { " (obrigatória)" } else { "" };
+    let text = format!("{}{}", escape(&flag.description), required);
+    if !text.is_empty() {
+        entry.push_str(&format!("{}\n", text));
+    }
+
+    if let Some(ref possible) = flag.possible_values {
+        entry.push_str(&format!(".br\nValores possíveis: {}\n", escape(&possible.join(", "))));
+    }
+
+    if let Some(ref env) = flag.env {
+        entry.push_str(&format!(".br\nVariável de ambiente: {}\n", escape(env)));
+    }
+
+    if let Some(ref default) = flag.default_value {
+        entry.push_str(&format!(".br\nPadrão: {}\n", escape(&default.to_string())));
+    }
+
+    entry
+}
+
+fn first_line(text: &str) -> &str {
+    text.lines().next().unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests;
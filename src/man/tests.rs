@@ -0,0 +1,72 @@
+use super::*;
+use crate::command::PositionalArg;
+use crate::flag::{FlagType, FlagValue};
+use crate::test_utils::TempDir;
+
+fn test_app() -> App {
+    App::new("app", "1.0.0")
+        .description("Aplicação de teste")
+        .add_global_flag(Flag::new("verbose", FlagType::Bool).short('v').description("Modo verboso"))
+        .add_command(
+            Command::new("calc")
+                .description("Calculadora")
+                .add_subcommand(
+                    Command::new("add")
+                        .description("Soma números")
+                        .add_flag(
+                            Flag::new("numbers", FlagType::IntegerList)
+                                .description("Números a somar")
+                                .required(true),
+                        )
+                        .add_flag(
+                            Flag::new("format", FlagType::String)
+                                .possible_values(vec!["json".to_string(), "text".to_string()])
+                                .default_value(FlagValue::String("text".to_string())),
+                        )
+                        .add_positional_arg(PositionalArg::new("label").required(false)),
+                ),
+        )
+        .add_command(Command::new("internal").hidden(true))
+}
+
+#[test]
+fn test_one_page_per_visible_command() {
+    let names: Vec<String> = render(&test_app()).into_iter().map(|page| page.name).collect();
+    assert_eq!(names, vec!["app", "app-calc", "app-calc-add"]);
+}
+
+#[test]
+fn test_page_sections() {
+    let pages = render(&test_app());
+    let add = &pages[2].content;
+
+    assert!(add.starts_with(".TH APP\\-CALC\\-ADD 1 \"\" \"app 1.0.0\"\n"));
+    assert!(add.contains(".SH NAME\napp\\-calc\\-add \\- Soma números\n"));
+    assert!(add.contains(".SH SYNOPSIS\n\\fBapp calc add\\fR [OPÇÕES] [label]\n"));
+    assert!(add.contains(".SH DESCRIPTION\nSoma números\n"));
+    assert!(add.contains(".TP\n\\fB\\-\\-numbers\\fR \\fI<integer list>\\fR\nNúmeros a somar (obrigatória)\n"));
+    assert!(add.contains("Valores possíveis: json, text\n"));
+    assert!(add.contains("Padrão: text\n"));
+    assert!(add.contains(".SH GLOBAL OPTIONS\n.TP\n\\fB\\-v\\fR, \\fB\\-\\-verbose\\fR\nModo verboso\n"));
+    assert!(add.contains(".SH ARGUMENTS\n.TP\n\\fIlabel\\fR (opcional)\n"));
+    assert!(add.contains(".SH SEE ALSO\n.BR app\\-calc (1)\n"));
+
+    let root = &pages[0].content;
+    assert!(root.contains(".SH SEE ALSO\n.BR app\\-calc (1)\n"));
+    assert!(!root.contains("internal"));
+}
+
+#[test]
+fn test_escape() {
+    assert_eq!(escape("a-b \\ c"), "a\\-b \\e c");
+    assert_eq!(escape(".linha\n'outra"), "\\&.linha\n\\&'outra");
+}
+
+#[test]
+fn test_write_to_dir() {
+    let dir = TempDir::new("man");
+
+    let written = test_app().write_man_pages(dir.path()).unwrap();
+    assert_eq!(written.len(), 3);
+    assert!(dir.path().join("app-calc-add.1").is_file());
+}
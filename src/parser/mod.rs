@@ -1,13 +1,64 @@
+use crate::command::GroupKind;
+use crate::config::ConfigValues;
+use crate::de::ParsedArgsDeserializer;
+use crate::env::EnvSource;
 use crate::flag::FlagValue;
-use crate::{AppError, Command, FlagType};
+use crate::log_level::LogLevel;
+use crate::suggestions::did_you_mean;
+use crate::value_parser::{self, AnyValue};
+use crate::{AppError, Command, Flag, FlagType};
+use std::any::Any;
 use std::collections::HashMap;
+use std::fmt;
+use std::path::PathBuf;
 
+/// De onde veio o valor de uma flag em `ParsedArgs`
+#[derive(Debug, Clone, PartialEq)]
+pub enum ValueSource {
+    /// Informado na linha de comando
+    CommandLine,
+    /// Lido da variável de ambiente com este nome
+    Environment(String),
+    /// Lido do arquivo de configuração neste caminho
+    ConfigFile(PathBuf),
+    /// Valor padrão da flag
+    Default,
+    /// Obtido interativamente do usuário
+    Prompt,
+}
+
+impl fmt::Display for ValueSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ValueSource::CommandLine => write!(f, "linha de comando"),
+            ValueSource::Environment(var) => write!(f, "variável de ambiente {}", var),
+            ValueSource::ConfigFile(path) => write!(f, "arquivo de configuração {}", path.display()),
+            ValueSource::Default => write!(f, "valor padrão"),
+            ValueSource::Prompt => write!(f, "prompt interativo"),
+        }
+    }
+}
+
+/// Resultado do parsing de um nível da árvore de comandos. As flags e argumentos
+/// de cada subcomando ficam no seu próprio `ParsedArgs`, acessível por
+/// `subcommand_matches`
 #[derive(Debug, Clone)]
 pub struct ParsedArgs {
     pub command: String,
     pub subcommand: Option<String>,
+    pub subcommand_matches: Option<Box<ParsedArgs>>,
     pub flags: HashMap<String, FlagValue>,
+    /// Origem de cada valor em `flags`
+    pub sources: HashMap<String, ValueSource>,
+    /// Valores produzidos pelo `ValueParser` das flags deste nível
+    pub typed_values: HashMap<String, Vec<AnyValue>>,
     pub positional_args: Vec<String>,
+    /// Nomes dos argumentos posicionais declarados no comando, na mesma ordem
+    pub positional_names: Vec<String>,
+    /// Tipo de cada flag visível neste nível, informada ou não
+    pub flag_types: HashMap<String, FlagType>,
+    /// Argumentos após `--` que excedem os argumentos posicionais declarados
+    pub trailing_args: Vec<String>,
     pub help_requested: bool,
 }
 
@@ -16,34 +67,275 @@ impl ParsedArgs {
         Self {
             command,
             subcommand: None,
+            subcommand_matches: None,
             flags: HashMap::new(),
+            sources: HashMap::new(),
+            typed_values: HashMap::new(),
             positional_args: Vec::new(),
+            positional_names: Vec::new(),
+            flag_types: HashMap::new(),
+            trailing_args: Vec::new(),
             help_requested: false,
         }
     }
 
+    /// Busca a flag começando pelo subcomando mais profundo e subindo até este nível
     pub fn get_flag(&self, flag: &str) -> Option<&FlagValue> {
-        self.flags.get(flag)
+        self.subcommand_matches()
+            .and_then(|sub| sub.get_flag(flag))
+            .or_else(|| self.flags.get(flag))
     }
 
     pub fn has_flag(&self, flag: &str) -> bool {
-        self.flags.contains_key(flag)
+        self.get_flag(flag).is_some()
+    }
+
+    /// Valor da flag convertido para `T`, buscado da mesma forma que `get_flag`. Usa o
+    /// `ValueParser` da flag ou, sem ele, o tipo de `FlagType` (`bool`, `String`,
+    /// `i64` ou `f64`). Em flags de lista retorna o primeiro valor
+    pub fn get<T>(&self, flag: &str) -> Result<Option<T>, AppError>
+    where
+        T: Any + Clone,
+    {
+        Ok(self.get_many(flag)?.and_then(|values| values.into_iter().next()))
+    }
+
+    /// Todos os valores da flag convertidos para `T`, como `get`
+    pub fn get_many<T>(&self, flag: &str) -> Result<Option<Vec<T>>, AppError>
+    where
+        T: Any + Clone,
+    {
+        let Some(level) = self.flag_level(flag) else {
+            return Ok(None);
+        };
+
+        let builtin;
+        let values = match level.typed_values.get(flag) {
+            Some(values) => values,
+            None => {
+                builtin = value_parser::builtin_values(&level.flags[flag]);
+                &builtin
+            }
+        };
+
+        values
+            .iter()
+            .map(|value| {
+                value.downcast_ref::<T>().cloned().ok_or_else(|| AppError::ValueTypeMismatch {
+                    flag: flag.to_string(),
+                    expected: std::any::type_name::<T>().to_string(),
+                    actual: value.type_name().to_string(),
+                })
+            })
+            .collect::<Result<Vec<T>, AppError>>()
+            .map(Some)
+    }
+
+    /// Valor de um contador (`FlagType::Count`), buscado da mesma forma que
+    /// `get_flag`, já descontado o contador negativo. Zero quando a flag não aparece
+    pub fn count(&self, flag: &str) -> i64 {
+        self.get_flag(flag).and_then(FlagValue::as_integer).unwrap_or(0)
+    }
+
+    /// Nível de log a partir de `base`, subindo um nível para cada unidade do
+    /// contador `flag` (ex: `-vv` a partir de `Warn` dá `Debug`) e descendo quando
+    /// ele é negativo
+    pub fn log_level(&self, flag: &str, base: LogLevel) -> LogLevel {
+        base.offset(self.count(flag))
+    }
+
+    /// Nível que contém a flag, do subcomando mais profundo até este
+    fn flag_level(&self, flag: &str) -> Option<&ParsedArgs> {
+        self.subcommand_matches()
+            .and_then(|sub| sub.flag_level(flag))
+            .or_else(|| self.flags.contains_key(flag).then_some(self))
     }
 
+    /// Origem do valor da flag, buscada da mesma forma que `get_flag`
+    pub fn value_source(&self, flag: &str) -> Option<&ValueSource> {
+        self.subcommand_matches()
+            .and_then(|sub| sub.value_source(flag))
+            .or_else(|| self.sources.get(flag))
+    }
+
+    fn set_flag(&mut self, name: &str, value: FlagValue, source: ValueSource) {
+        self.flags.insert(name.to_string(), value);
+        self.sources.insert(name.to_string(), source);
+    }
+
+    /// Argumento posicional do subcomando mais profundo
     pub fn get_arg(&self, arg: usize) -> Option<&String> {
-        self.positional_args.get(arg)
+        self.deepest_matches().positional_args.get(arg)
     }
 
+    /// Argumentos posicionais do subcomando mais profundo
     pub fn get_args(&self) -> &Vec<String> {
-        &self.positional_args
+        &self.deepest_matches().positional_args
+    }
+
+    pub fn get_trailing_args(&self) -> &Vec<String> {
+        &self.deepest_matches().trailing_args
+    }
+
+    /// Resultado do parsing do subcomando imediatamente abaixo deste nível
+    pub fn subcommand_matches(&self) -> Option<&ParsedArgs> {
+        self.subcommand_matches.as_deref()
+    }
+
+    /// Resultado do parsing do último subcomando encontrado (ou este nível)
+    pub fn deepest_matches(&self) -> &ParsedArgs {
+        let mut current = self;
+        while let Some(sub) = current.subcommand_matches() {
+            current = sub;
+        }
+        current
+    }
+
+    /// Argumento posicional deste nível pelo nome declarado em `PositionalArg`
+    pub fn get_arg_by_name(&self, name: &str) -> Option<&String> {
+        let index = self.positional_names.iter().position(|arg| arg == name)?;
+        self.positional_args.get(index)
+    }
+
+    /// Converte este nível em `T` com serde: flags e argumentos posicionais viram
+    /// campos (`dry_run` corresponde a `--dry-run`) e o subcomando escolhido vira o
+    /// enum do campo `command` ou `subcommand`. Veja `de::ParsedArgsDeserializer`
+    pub fn deserialize<'de, T>(&'de self) -> Result<T, AppError>
+    where
+        T: serde::Deserialize<'de>,
+    {
+        T::deserialize(ParsedArgsDeserializer::new(self)).map_err(AppError::from)
+    }
+
+    /// Caminho completo de subcomandos abaixo deste nível, ex: `["calc", "add"]`
+    pub fn command_path(&self) -> Vec<&str> {
+        let mut path = Vec::new();
+        let mut current = self;
+        while let Some(sub) = current.subcommand_matches() {
+            path.push(sub.command.as_str());
+            current = sub;
+        }
+        path
     }
 }
 
+/// Flags aceitas em um nível da árvore: as do próprio comando e as globais
+/// herdadas dos comandos acima dele
+struct FlagScope<'a> {
+    root: &'a Command,
+    command: &'a Command,
+    path: Vec<&'a str>,
+    inherited: Vec<&'a Flag>,
+    env: &'a EnvSource,
+    config: &'a ConfigValues,
+}
+
+impl<'a> FlagScope<'a> {
+    fn new(command: &'a Command, env: &'a EnvSource, config: &'a ConfigValues) -> Self {
+        Self {
+            root: command,
+            command,
+            path: Vec::new(),
+            inherited: Vec::new(),
+            env,
+            config,
+        }
+    }
+
+    fn for_subcommand(&self, subcommand: &'a Command) -> Self {
+        let mut inherited = self.inherited.clone();
+        inherited.extend(self.command.flags.values().filter(|flag| flag.global));
+
+        let mut path = self.path.clone();
+        path.push(&subcommand.name);
+
+        Self {
+            root: self.root,
+            command: subcommand,
+            path,
+            inherited,
+            env: self.env,
+            config: self.config,
+        }
+    }
+
+    fn visible_flags(&self) -> impl Iterator<Item = &'a Flag> {
+        self.command
+            .flags
+            .values()
+            .chain(self.inherited.iter().copied())
+    }
+
+    fn get_flag(&self, name: &str) -> Option<&'a Flag> {
+        if let Some(flag) = self.command.get_flag(name) {
+            return Some(flag);
+        }
+
+        let mut chars = name.chars();
+        let short = chars.next().filter(|_| chars.next().is_none());
+
+        self.inherited
+            .iter()
+            .copied()
+            .find(|flag| flag.name == name || (short.is_some() && flag.short == short))
+    }
+
+    /// Flag negável desativada por `name` na forma `no-<nome>`, ex: `no-color`
+    fn get_negated_flag(&self, name: &str) -> Option<&'a Flag> {
+        self.visible_flags()
+            .find(|flag| flag.negated_name().as_deref() == Some(name))
+    }
+}
+
+/// Onde o cursor está no autocompletar, calculado a partir das palavras
+/// anteriores a ele com as mesmas regras do parsing
+pub(crate) struct CompletionContext<'a> {
+    pub command: &'a Command,
+    /// Flags aceitas no comando atual, incluindo as globais herdadas
+    pub flags: Vec<&'a Flag>,
+    /// Flag que ainda espera um valor, ex: `--format` em `app --format <cursor>`
+    pub pending_flag: Option<&'a Flag>,
+    pub after_terminator: bool,
+}
+
+static PROCESS_ENV: EnvSource = EnvSource::Process;
+static EMPTY_CONFIG: ConfigValues = ConfigValues { layers: Vec::new() };
+
 pub struct CLIParser;
 
 impl CLIParser {
     pub fn parse(command: &Command, args: Vec<String>) -> Result<ParsedArgs, AppError> {
+        Self::parse_with_env(command, args, &EnvSource::Process)
+    }
+
+    /// Igual a `parse`, lendo as variáveis de ambiente das flags a partir de `env`
+    pub fn parse_with_env(
+        command: &Command,
+        args: Vec<String>,
+        env: &EnvSource,
+    ) -> Result<ParsedArgs, AppError> {
+        Self::parse_with_config(command, args, env, &ConfigValues::new())
+    }
+
+    /// Igual a `parse_with_env`, usando também os valores de arquivos de configuração.
+    /// Precedência: linha de comando > variável de ambiente > configuração > valor padrão
+    pub fn parse_with_config(
+        command: &Command,
+        args: Vec<String>,
+        env: &EnvSource,
+        config: &ConfigValues,
+    ) -> Result<ParsedArgs, AppError> {
+        Self::parse_scope(&FlagScope::new(command, env, config), args)
+    }
+
+    fn parse_scope(scope: &FlagScope, args: Vec<String>) -> Result<ParsedArgs, AppError> {
+        let command = scope.command;
         let mut parsed = ParsedArgs::new(command.name.clone());
+        parsed.positional_names = command.positional_args.iter().map(|arg| arg.name.clone()).collect();
+        parsed.flag_types = scope
+            .visible_flags()
+            .map(|flag| (flag.name.clone(), flag.flag_type.clone()))
+            .collect();
         let mut i = 0;
 
         if args.is_empty() && command.show_help_on_empty {
@@ -54,106 +346,613 @@ impl CLIParser {
         while i < args.len() {
             let arg = &args[i];
 
-            if arg == "--help" || arg == "-h" {
+            if Self::is_help(arg) {
                 parsed.help_requested = true;
                 return Ok(parsed);
             }
 
-            if arg.starts_with("--") {
-                let flag_name: &str = &arg[2..];
-                i += Self::parse_long_flag(command, &args, &mut i, flag_name, &mut parsed)?;
-            } else if arg.starts_with("-") && arg.len() == 2 {
-                let flag_char = arg.chars().nth(1).unwrap();
-                i += Self::parse_short_flag(command, &args, &mut i, flag_char, &mut parsed)?;
+            if arg == "--" {
+                Self::push_after_terminator(command, &args[i + 1..], &mut parsed);
+                break;
+            }
+
+            if Self::is_hyphen_positional(scope, parsed.positional_args.len(), arg) {
+                parsed.positional_args.push(arg.clone());
+                i += 1;
+            } else if let Some(flag_arg) = arg.strip_prefix("--") {
+                let (flag_name, inline_value) = match flag_arg.split_once('=') {
+                    Some((name, value)) => (name, Some(value)),
+                    None => (flag_arg, None),
+                };
+                i += Self::parse_long_flag(scope, &args, i, flag_name, inline_value, &mut parsed)?;
+            } else if arg.starts_with('-') && arg.len() > 1 {
+                i += Self::parse_short_flag(scope, &args, i, &arg[1..], &mut parsed)?;
             } else if let Some(subcommand) = command.get_subcommand(arg) {
                 parsed.subcommand = Some(arg.clone());
                 let remaining_args = args[i + 1..].to_vec();
-                let sub_parsed = Self::parse(subcommand, remaining_args)?;
+                let sub_parsed =
+                    Self::parse_scope(&scope.for_subcommand(subcommand), remaining_args)?;
 
-                parsed.flags.extend(sub_parsed.flags);
-                parsed.positional_args.extend(sub_parsed.positional_args);
                 parsed.help_requested = sub_parsed.help_requested;
+                parsed.subcommand_matches = Some(Box::new(sub_parsed));
 
                 break;
             } else if command.has_positional_args() {
                 parsed.positional_args.push(arg.clone());
                 i += 1;
             }else {
-                return Err(AppError::CommandNotFound { command: arg.clone() })
+                return Err(AppError::CommandNotFound {
+                    command: arg.clone(),
+                    suggestions: did_you_mean(arg, command.subcommands.keys().map(String::as_str)),
+                })
             }
         }
 
+        if parsed.help_requested {
+            return Ok(parsed);
+        }
+
         if command.has_positional_args() {
             Self::validate_positional_args(command, &parsed)?;
         }
 
-        Self::apply_defaults_and_validate(command, &mut parsed)?;
+        Self::collect_global_flags(command, &mut parsed)?;
+
+        Self::apply_defaults_and_validate(scope, &mut parsed)?;
+
+        Self::apply_value_parsers(command, &mut parsed)?;
 
         Ok(parsed)
     }
 
+    /// Percorre `args` (as palavras antes do cursor) como `parse_scope`, sem validar
+    /// nada, para descobrir o subcomando atual e se o cursor está no valor de uma flag
+    pub(crate) fn completion_context<'a>(root: &'a Command, args: &[String]) -> CompletionContext<'a> {
+        Self::walk_args(root, args, |_, _| {})
+    }
+
+    /// Último valor informado para a flag `flag_name` do comando raiz, encontrado com
+    /// as mesmas regras do parsing (`--config caminho`, `--config=caminho`, `-vc caminho`,
+    /// `-ccaminho`). Flags homônimas de subcomandos e valores de outras flags são ignorados
+    pub(crate) fn root_flag_value(root: &Command, flag_name: &str, args: &[String]) -> Option<String> {
+        let target = root.flags.get(flag_name)?;
+        let mut found = None;
+
+        Self::walk_args(root, args, |flag, value| {
+            if std::ptr::eq(flag, target) {
+                found = Some(value.to_string());
+            }
+        });
+
+        found
+    }
+
+    /// Percorre `args` como `parse_scope`, sem validar nada, chamando `on_value` para
+    /// cada valor de flag encontrado. Retorna onde o percurso terminou
+    fn walk_args<'a>(
+        root: &'a Command,
+        args: &[String],
+        mut on_value: impl FnMut(&'a Flag, &str),
+    ) -> CompletionContext<'a> {
+        let mut scope = FlagScope::new(root, &PROCESS_ENV, &EMPTY_CONFIG);
+        let mut pending_flag: Option<&Flag> = None;
+        let mut pending_values = 0;
+        let mut pending_args = 0;
+        let mut pending_limit = usize::MAX;
+        let mut positional_count = 0;
+        let mut after_terminator = false;
+
+        for (index, arg) in args.iter().enumerate() {
+            // Mesmas regras de `apply_flag`: uma flag de lista continua esperando
+            // valores até a próxima flag, subcomando, o máximo de `num_values` ou
+            // as palavras que faltam aos argumentos posicionais obrigatórios
+            if let Some(flag) = pending_flag.take() {
+                let accepted = if pending_values == 0 {
+                    Self::is_separate_value(&scope, flag, arg)
+                } else {
+                    Self::is_next_list_value(&scope, flag, arg)
+                        && (pending_args < pending_limit || pending_values < flag.value_range().min)
+                };
+
+                if accepted {
+                    on_value(flag, arg);
+                    pending_values += flag.split_values(arg).len();
+                    pending_args += 1;
+                    let full = flag.value_range().max.is_some_and(|max| pending_values >= max);
+                    if flag.flag_type.is_list() && !full {
+                        pending_flag = Some(flag);
+                    }
+                    continue;
+                }
+            }
+            pending_values = 0;
+            pending_args = 0;
+
+            if arg == "--" {
+                after_terminator = true;
+                break;
+            }
+
+            let valued_flag = if Self::is_hyphen_positional(&scope, positional_count, arg) {
+                positional_count += 1;
+                None
+            } else if let Some(flag_arg) = arg.strip_prefix("--") {
+                let (flag_name, inline_value) = match flag_arg.split_once('=') {
+                    Some((name, value)) => (name, Some(value)),
+                    None => (flag_arg, None),
+                };
+                scope
+                    .get_flag(flag_name)
+                    .filter(|flag| flag.flag_type.takes_value())
+                    .map(|flag| (flag, inline_value))
+            } else if arg.starts_with('-') && arg.len() > 1 {
+                Self::short_flag_value(&scope, &arg[1..])
+            } else if let Some(subcommand) = scope.command.get_subcommand(arg) {
+                scope = scope.for_subcommand(subcommand);
+                positional_count = 0;
+                None
+            } else {
+                positional_count += 1;
+                None
+            };
+
+            match valued_flag {
+                Some((flag, Some(value))) => on_value(flag, value),
+                Some((flag, None)) => {
+                    pending_flag = Some(flag);
+                    pending_limit = Self::list_value_limit(&scope, positional_count, &args[index + 1..]);
+                }
+                None => {}
+            }
+        }
+
+        CompletionContext {
+            command: scope.command,
+            flags: scope.visible_flags().collect(),
+            pending_flag,
+            after_terminator,
+        }
+    }
+
+    /// Primeira flag que recebe valor em um grupo de flags curtas e o valor anexado,
+    /// se houver, ex: `f` em `-xzf` ou `n` e `5` em `-vn5`
+    fn short_flag_value<'a, 'b>(
+        scope: &FlagScope<'a>,
+        cluster: &'b str,
+    ) -> Option<(&'a Flag, Option<&'b str>)> {
+        for (offset, flag_char) in cluster.char_indices() {
+            let flag = scope.get_flag(&flag_char.to_string())?;
+            let rest = &cluster[offset + flag_char.len_utf8()..];
+
+            if flag.flag_type.takes_value() {
+                let inline_value = (!rest.is_empty()).then(|| rest.strip_prefix('=').unwrap_or(rest));
+                return Some((flag, inline_value));
+            }
+            if rest.starts_with('=') {
+                return None;
+            }
+        }
+
+        None
+    }
+
+    /// Processa uma flag longa (`--name valor`, `--name=valor` ou `--no-name`) e
+    /// retorna quantos elementos de `args` foram consumidos a partir de `i`
     fn parse_long_flag(
-        command: &Command,
+        scope: &FlagScope,
         args: &[String],
-        i: &mut usize,
+        i: usize,
         flag_name: &str,
+        inline_value: Option<&str>,
         parsed: &mut ParsedArgs,
     ) -> Result<usize, AppError> {
-        let flag = command
-            .get_flag(flag_name)
-            .ok_or_else(|| AppError::UnknownFlag {
+        if let Some(flag) = scope.get_flag(flag_name) {
+            return Self::apply_flag(scope, flag, args, i, inline_value, parsed);
+        }
+
+        let flag = scope
+            .get_negated_flag(flag_name)
+            .ok_or_else(|| Self::unknown_flag(scope, flag_name))?;
+
+        if let Some(value) = inline_value {
+            return Err(AppError::FlagDoesNotTakeValue {
                 flag: flag_name.to_string(),
-            })?;
-
-        match flag.flag_type {
-            FlagType::Bool => {
-                parsed
-                    .flags
-                    .insert(flag_name.to_string(), FlagValue::Bool(true));
-                Ok(1)
-            }
-            _ => {
-                if *i + 1 >= args.len() {
-                    return Err(AppError::FlagValueMissing {
-                        flag: flag_name.to_string(),
-                    });
+                value: value.to_string(),
+            });
+        }
+
+        // A última ocorrência vence: `--color --no-color` resulta em false
+        parsed.set_flag(&flag.name, FlagValue::Bool(false), ValueSource::CommandLine);
+        Ok(1)
+    }
+
+    /// Processa um grupo de flags curtas (`-v`, `-vq`, `-xzf arquivo`, `-nvalor`), onde
+    /// `cluster` é o argumento sem o `-` inicial. Flags booleanas e contadores são
+    /// aplicados um a um (`-vvv` conta três) e a primeira flag que recebe valor
+    /// consome o restante do grupo ou o próximo argumento
+    fn parse_short_flag(
+        scope: &FlagScope,
+        args: &[String],
+        i: usize,
+        cluster: &str,
+        parsed: &mut ParsedArgs,
+    ) -> Result<usize, AppError> {
+        for (offset, flag_char) in cluster.char_indices() {
+            let flag = scope
+                .get_flag(&flag_char.to_string())
+                .ok_or_else(|| Self::unknown_short_flag(scope, cluster, offset, flag_char))?;
+
+            let rest = &cluster[offset + flag_char.len_utf8()..];
+
+            if !flag.flag_type.takes_value() && !rest.starts_with('=') {
+                Self::apply_flag(scope, flag, args, i, None, parsed)?;
+                continue;
+            }
+
+            let inline_value = if rest.is_empty() {
+                None
+            } else {
+                Some(rest.strip_prefix('=').unwrap_or(rest))
+            };
+
+            return Self::apply_flag(scope, flag, args, i, inline_value, parsed);
+        }
+
+        Ok(1)
+    }
+
+    fn unknown_short_flag(
+        scope: &FlagScope,
+        cluster: &str,
+        offset: usize,
+        flag_char: char,
+    ) -> AppError {
+        if cluster.chars().count() == 1 {
+            return Self::unknown_flag(scope, &flag_char.to_string());
+        }
+
+        AppError::UnknownFlagInCluster {
+            flag: flag_char,
+            cluster: cluster.to_string(),
+            position: cluster[..offset].chars().count() + 1,
+        }
+    }
+
+    /// Monta o erro de flag desconhecida com sugestões: flags parecidas visíveis
+    /// neste nível e, em seguida, comandos da árvore que definem exatamente essa flag
+    fn unknown_flag(scope: &FlagScope, flag_name: &str) -> AppError {
+        let mut suggestions: Vec<String> = if flag_name.chars().count() > 1 {
+            did_you_mean(flag_name, scope.visible_flags().map(|flag| flag.name.as_str()))
+                .into_iter()
+                .map(|name| format!("--{}", name))
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        let mut path = vec![scope.root.name.as_str()];
+        Self::find_flag_in_tree(scope.root, scope.command, flag_name, &mut path, &mut suggestions);
+
+        AppError::UnknownFlag {
+            flag: flag_name.to_string(),
+            suggestions,
+        }
+    }
+
+    fn find_flag_in_tree<'a>(
+        command: &'a Command,
+        current: &Command,
+        flag_name: &str,
+        path: &mut Vec<&'a str>,
+        found: &mut Vec<String>,
+    ) {
+        if !std::ptr::eq(command, current)
+            && let Some(flag) = command.get_flag(flag_name)
+        {
+            found.push(format!("{} --{}", path.join(" "), flag.name));
+        }
+
+        for subcommand in command.get_subcommands_sorted() {
+            path.push(&subcommand.name);
+            Self::find_flag_in_tree(subcommand, current, flag_name, path, found);
+            path.pop();
+        }
+    }
+
+    fn apply_flag(
+        scope: &FlagScope,
+        flag: &Flag,
+        args: &[String],
+        i: usize,
+        inline_value: Option<&str>,
+        parsed: &mut ParsedArgs,
+    ) -> Result<usize, AppError> {
+        if let FlagType::Bool = flag.flag_type {
+            // Apenas `--flag=valor` informa o valor; `--flag valor` deixa `valor` como argumento
+            let value = match inline_value {
+                Some(value) => flag.parse_value(value)?,
+                None => FlagValue::Bool(true),
+            };
+            parsed.set_flag(&flag.name, value, ValueSource::CommandLine);
+            return Ok(1);
+        }
+
+        if let FlagType::Count = flag.flag_type {
+            if let Some(value) = inline_value {
+                return Err(AppError::FlagDoesNotTakeValue {
+                    flag: flag.name.clone(),
+                    value: value.to_string(),
+                });
+            }
+            Self::insert_flag_value(flag, FlagValue::Integer(1), parsed)?;
+            return Ok(1);
+        }
+
+        if flag.flag_type.is_list() {
+            return Self::apply_list_flag(scope, flag, args, i, inline_value, parsed);
+        }
+
+        let (raw_value, consumed) = match inline_value {
+            Some(value) => (value, 1),
+            None => match args.get(i + 1).filter(|value| Self::is_separate_value(scope, flag, value)) {
+                Some(value) => (value.as_str(), 2),
+                None if Self::help_follows(args, i) => return Ok(1),
+                None => {
+                    // Flag com valor opcional informada sem valor (`--color`)
+                    let value = flag.default_missing_value.clone().ok_or_else(|| {
+                        AppError::FlagValueMissing {
+                            flag: flag.name.clone(),
+                        }
+                    })?;
+                    Self::insert_flag_value(flag, value, parsed)?;
+                    return Ok(1);
                 }
+            },
+        };
 
-                *i += 1;
-                let value = flag.parse_value(&args[*i])?;
+        let value = flag.parse_value(raw_value)?;
+        Self::insert_flag_value(flag, value, parsed)?;
+
+        Ok(consumed)
+    }
 
-                if matches!(flag.flag_type, FlagType::StringList | FlagType::IntegerList) {
-                    if let Some(existing) = parsed.flags.get(&flag.name) {
-                        let combined = Self::combine_list_values(existing, &value)?;
-                        parsed.flags.insert(flag.name.clone(), combined);
+    /// Processa uma ocorrência de uma flag de lista. Sem valor anexado, consome
+    /// argumentos até a próxima flag, subcomando ou `--`, ou até o máximo de
+    /// `num_values`, deixando as palavras que faltam aos argumentos posicionais
+    /// obrigatórios. Cada argumento é dividido em `value_delimiter`
+    fn apply_list_flag(
+        scope: &FlagScope,
+        flag: &Flag,
+        args: &[String],
+        i: usize,
+        inline_value: Option<&str>,
+        parsed: &mut ParsedArgs,
+    ) -> Result<usize, AppError> {
+        let range = flag.value_range();
+        let mut values = Vec::new();
+        let mut consumed = 1;
+
+        match inline_value {
+            Some(value) => values.extend(flag.split_values(value)),
+            None => {
+                let limit = Self::list_value_limit(scope, parsed.positional_args.len(), &args[i + 1..]);
+                for arg in &args[i + 1..] {
+                    let accepted = if consumed == 1 {
+                        Self::is_separate_value(scope, flag, arg)
                     } else {
-                        parsed.flags.insert(flag.name.clone(), value);
+                        Self::is_next_list_value(scope, flag, arg)
+                    };
+                    if !accepted
+                        || range.max.is_some_and(|max| values.len() >= max)
+                        || (consumed > limit && values.len() >= range.min.max(1))
+                    {
+                        break;
                     }
-                } else {
-                    parsed.flags.insert(flag.name.clone(), value);
-                }
 
-                Ok(1)
+                    values.extend(flag.split_values(arg));
+                    consumed += 1;
+                }
             }
         }
+
+        if values.is_empty()
+            && let Some(default_missing) = &flag.default_missing_value
+        {
+            Self::insert_flag_value(flag, default_missing.clone(), parsed)?;
+            return Ok(consumed);
+        }
+
+        if values.is_empty() && Self::help_follows(args, i) {
+            return Ok(consumed);
+        }
+
+        if values.is_empty() && range.min > 0 {
+            return Err(AppError::FlagValueMissing {
+                flag: flag.name.clone(),
+            });
+        }
+
+        if !range.contains(values.len()) {
+            return Err(AppError::InvalidValueCount {
+                flag: flag.name.clone(),
+                expected: range.to_string(),
+                received: values.len(),
+            });
+        }
+
+        let value = flag.parse_values(&values)?;
+        Self::insert_flag_value(flag, value, parsed)?;
+
+        Ok(consumed)
     }
 
-    fn parse_short_flag(
-        command: &Command,
-        args: &[String],
-        i: &mut usize,
-        flag_char: char,
+    fn insert_flag_value(
+        flag: &Flag,
+        value: FlagValue,
         parsed: &mut ParsedArgs,
-    ) -> Result<usize, AppError> {
-        let flag = command
-            .flags
-            .values()
-            .find(|f| f.short == Some(flag_char))
-            .ok_or_else(|| AppError::UnknownFlag {
-                flag: flag_char.to_string(),
-            })?;
+    ) -> Result<(), AppError> {
+        if matches!(
+            flag.flag_type,
+            FlagType::StringList | FlagType::IntegerList | FlagType::Count
+        ) && let Some(existing) = parsed.flags.get(&flag.name)
+        {
+            let combined = Self::combine_list_values(existing, &value)?;
+            parsed.set_flag(&flag.name, combined, ValueSource::CommandLine);
+            return Ok(());
+        }
+
+        parsed.set_flag(&flag.name, value, ValueSource::CommandLine);
+        Ok(())
+    }
+
+    /// Distribui os argumentos após `--` sem interpretá-los: primeiro preenchem os
+    /// argumentos posicionais ainda livres e o restante vai para `trailing_args`
+    fn push_after_terminator(command: &Command, rest: &[String], parsed: &mut ParsedArgs) {
+        let free_slots = command
+            .positional_args
+            .len()
+            .saturating_sub(parsed.positional_args.len())
+            .min(rest.len());
+
+        parsed.positional_args.extend_from_slice(&rest[..free_slots]);
+        parsed.trailing_args.extend_from_slice(&rest[free_slots..]);
+    }
+
+    /// Verifica se `arg`, o argumento seguinte a `flag`, é o valor dela. Flags com
+    /// valor opcional também não consomem nomes de subcomandos
+    fn is_separate_value(scope: &FlagScope, flag: &Flag, arg: &str) -> bool {
+        if !flag.allow_hyphen_values && Self::looks_like_flag(arg) {
+            return false;
+        }
+
+        flag.default_missing_value.is_none() || scope.command.get_subcommand(arg).is_none()
+    }
+
+    /// Verifica se `arg` continua os valores de uma flag de lista: não pode ser
+    /// `--`, uma flag conhecida (ou qualquer flag, sem `allow_hyphen_values`) nem
+    /// um subcomando
+    fn is_next_list_value(scope: &FlagScope, flag: &Flag, arg: &str) -> bool {
+        if Self::looks_like_flag(arg)
+            && (!flag.allow_hyphen_values || arg == "--" || Self::is_known_flag(scope, arg))
+        {
+            return false;
+        }
+
+        scope.command.get_subcommand(arg).is_none()
+    }
+
+    /// Quantos dos argumentos em `args`, que seguem uma flag de lista, ela pode
+    /// consumir sem tirar as palavras que faltam aos argumentos posicionais
+    /// obrigatórios, ex: `arquivo.txt` em `--tags a b arquivo.txt`
+    fn list_value_limit(scope: &FlagScope, positional_count: usize, args: &[String]) -> usize {
+        let missing = scope.command.required_positional_count().saturating_sub(positional_count);
+        if missing == 0 {
+            return usize::MAX;
+        }
+
+        let mut words = 0;
+        let mut args = args.iter();
+        while let Some(arg) = args.next() {
+            if arg == "--" {
+                words += args.count();
+                break;
+            }
+            if scope.command.get_subcommand(arg).is_some() {
+                break;
+            }
+
+            // Valores separados de outras flags não são argumentos posicionais
+            let valued_flag = if let Some(flag_arg) = arg.strip_prefix("--") {
+                scope
+                    .get_flag(flag_arg)
+                    .is_some_and(|flag| flag.flag_type.takes_value())
+            } else if Self::looks_like_flag(arg) {
+                matches!(Self::short_flag_value(scope, &arg[1..]), Some((_, None)))
+            } else {
+                words += 1;
+                false
+            };
+            if valued_flag {
+                args.next();
+            }
+        }
+
+        words.saturating_sub(missing)
+    }
+
+    fn is_help(arg: &str) -> bool {
+        arg == "--help" || arg == "-h"
+    }
+
+    /// Verifica se a flag em `i` ficou sem valor porque o próximo argumento pede
+    /// ajuda (`--name --help`); nesse caso a ajuda é exibida em vez do erro
+    fn help_follows(args: &[String], i: usize) -> bool {
+        args.get(i + 1).is_some_and(|arg| Self::is_help(arg))
+    }
+
+    fn looks_like_flag(arg: &str) -> bool {
+        arg.starts_with('-') && arg.len() > 1
+    }
 
-        Self::parse_long_flag(command, args, i, &flag.name, parsed)
+    /// Verifica se um argumento iniciado por `-` deve ser tratado como valor do
+    /// próximo argumento posicional em vez de flag
+    fn is_hyphen_positional(scope: &FlagScope, positional_count: usize, arg: &str) -> bool {
+        if !Self::looks_like_flag(arg) {
+            return false;
+        }
+
+        let accepts_hyphen = scope
+            .command
+            .positional_args
+            .get(positional_count)
+            .is_some_and(|positional| positional.allow_hyphen_values);
+
+        accepts_hyphen && !Self::is_known_flag(scope, arg)
+    }
+
+    fn is_known_flag(scope: &FlagScope, arg: &str) -> bool {
+        if let Some(flag_arg) = arg.strip_prefix("--") {
+            let name = flag_arg.split_once('=').map_or(flag_arg, |(name, _)| name);
+            return scope.get_flag(name).or_else(|| scope.get_negated_flag(name)).is_some();
+        }
+
+        arg[1..]
+            .chars()
+            .next()
+            .is_some_and(|flag_char| scope.get_flag(&flag_char.to_string()).is_some())
+    }
+
+    /// Move para este nível os valores das flags globais definidas aqui que foram
+    /// informadas depois de um subcomando, para que padrões e obrigatoriedade
+    /// sejam verificados apenas no comando que define a flag
+    fn collect_global_flags(command: &Command, parsed: &mut ParsedArgs) -> Result<(), AppError> {
+        let Some(sub_parsed) = parsed.subcommand_matches.as_deref_mut() else {
+            return Ok(());
+        };
+
+        let mut collected = Vec::new();
+        for flag in command.flags.values().filter(|flag| flag.global) {
+            let mut values = Vec::new();
+            Self::take_flag_values(sub_parsed, &flag.name, &mut values);
+            collected.extend(values.into_iter().map(|value| (flag, value)));
+        }
+
+        for (flag, value) in collected {
+            Self::insert_flag_value(flag, value, parsed)?;
+        }
+
+        Ok(())
+    }
+
+    fn take_flag_values(parsed: &mut ParsedArgs, name: &str, values: &mut Vec<FlagValue>) {
+        if let Some(value) = parsed.flags.remove(name) {
+            parsed.sources.remove(name);
+            values.push(value);
+        }
+
+        if let Some(sub_parsed) = parsed.subcommand_matches.as_deref_mut() {
+            Self::take_flag_values(sub_parsed, name, values);
+        }
     }
 
     fn combine_list_values(existing: &FlagValue, new: &FlagValue) -> Result<FlagValue, AppError> {
@@ -168,6 +967,10 @@ impl CLIParser {
                 combined.extend(new_list.clone());
                 Ok(FlagValue::IntegerList(combined))
             }
+            // Contadores (`FlagType::Count`) somam as ocorrências
+            (FlagValue::Integer(existing), FlagValue::Integer(new)) => {
+                Ok(FlagValue::Integer(existing.saturating_add(*new)))
+            }
             _ => Err(AppError::ParseError {
                 message: "Erro interno: tentativa de combinar valores incompativeis".to_string(),
             }),
@@ -193,16 +996,18 @@ impl CLIParser {
     }
 
     fn apply_defaults_and_validate(
-        command: &Command,
+        scope: &FlagScope,
         parsed: &mut ParsedArgs,
     ) -> Result<(), AppError> {
 
-        for flag in command.flags.values() {
+        for flag in scope.command.flags.values() {
             if !parsed.flags.contains_key(&flag.name) {
-                if let Some(default_value) = &flag.default_value {
-                    parsed
-                        .flags
-                        .insert(flag.name.clone(), default_value.clone());
+                if let Some((value, var)) = Self::read_env_value(flag, scope.env)? {
+                    parsed.set_flag(&flag.name, value, ValueSource::Environment(var));
+                } else if let Some((value, path)) = Self::lookup_config(scope, flag, parsed)? {
+                    parsed.set_flag(&flag.name, value, ValueSource::ConfigFile(path));
+                } else if let Some(default_value) = &flag.default_value {
+                    parsed.set_flag(&flag.name, default_value.clone(), ValueSource::Default);
                 } else if flag.required {
                     return Err(AppError::RequiredFlagNotProvided {
                         flag: flag.name.clone(),
@@ -211,10 +1016,168 @@ impl CLIParser {
             }
         }
 
+        Self::validate_relationships(scope.command, parsed)?;
+        Self::apply_counter_offsets(scope.command, parsed);
+        Ok(())
+    }
+
+    /// Valor de `flag` nos arquivos de configuração. Flags globais também podem ser
+    /// definidas na tabela de um subcomando usado; a tabela mais profunda tem precedência
+    fn lookup_config(
+        scope: &FlagScope,
+        flag: &Flag,
+        parsed: &ParsedArgs,
+    ) -> Result<Option<(FlagValue, PathBuf)>, AppError> {
+        let mut path = scope.path.clone();
+        if flag.global {
+            path.extend(parsed.command_path());
+        }
+
+        loop {
+            if let Some(found) = scope.config.lookup(&path, flag)? {
+                return Ok(Some(found));
+            }
+            if path.len() == scope.path.len() {
+                return Ok(None);
+            }
+            path.pop();
+        }
+    }
+
+    /// Subtrai o valor de cada contador negativo (`Flag::decrements`) do contador
+    /// que ele compensa. Se apenas o negativo tiver valor, o alvo fica negativo
+    /// com a mesma origem
+    fn apply_counter_offsets(command: &Command, parsed: &mut ParsedArgs) {
+        for flag in command.get_flags_sorted() {
+            let (Some(target), Some(FlagValue::Integer(count))) =
+                (&flag.decrements, parsed.flags.get(&flag.name))
+            else {
+                continue;
+            };
+            let count = *count;
+
+            match parsed.flags.get(target) {
+                Some(FlagValue::Integer(level)) => {
+                    let level = FlagValue::Integer(level.saturating_sub(count));
+                    parsed.flags.insert(target.clone(), level);
+                }
+                Some(_) => {}
+                None => {
+                    let source = parsed.sources[&flag.name].clone();
+                    parsed.set_flag(target, FlagValue::Integer(count.saturating_neg()), source);
+                }
+            }
+        }
+    }
+
+    /// Verifica `conflicts_with`, `requires`, `required_unless` e os grupos do comando.
+    /// Conflitos e grupos ignoram valores padrão; as demais regras consideram
+    /// qualquer valor
+    fn validate_relationships(command: &Command, parsed: &ParsedArgs) -> Result<(), AppError> {
+        let has_value = |name: &str| parsed.flags.contains_key(name);
+        let provided =
+            |name: &str| has_value(name) && parsed.sources.get(name) != Some(&ValueSource::Default);
+
+        for flag in command.get_flags_sorted() {
+            if provided(&flag.name)
+                && let Some(other) = flag.conflicts_with.iter().find(|other| provided(other))
+            {
+                return Err(AppError::ConflictingFlags {
+                    flag: flag.name.clone(),
+                    other: other.clone(),
+                });
+            }
+
+            if has_value(&flag.name) {
+                if let Some(required) = flag.requires.iter().find(|required| !has_value(required)) {
+                    return Err(AppError::MissingRequiredFlag {
+                        flag: flag.name.clone(),
+                        required: required.clone(),
+                    });
+                }
+            } else if !flag.required_unless.is_empty()
+                && !flag.required_unless.iter().any(|other| has_value(other))
+            {
+                return Err(AppError::RequiredUnlessNotProvided {
+                    flag: flag.name.clone(),
+                    alternatives: flag.required_unless.clone(),
+                });
+            }
+        }
+
+        for group in &command.groups {
+            let present: Vec<&String> = group.flags.iter().filter(|flag| provided(flag)).collect();
+
+            match (group.kind, present.as_slice()) {
+                (GroupKind::ExactlyOne | GroupKind::AtLeastOne, []) => {
+                    return Err(AppError::GroupNotSatisfied {
+                        group: group.name.clone(),
+                        kind: group.kind,
+                        flags: group.flags.clone(),
+                    });
+                }
+                (GroupKind::ExactlyOne | GroupKind::AtMostOne, [flag, other, ..]) => {
+                    return Err(AppError::GroupConflict {
+                        group: group.name.clone(),
+                        flag: (*flag).clone(),
+                        other: (*other).clone(),
+                    });
+                }
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Converte os valores finais das flags com `ValueParser`, vindos da linha de
+    /// comando, do ambiente, da configuração ou do valor padrão
+    fn apply_value_parsers(command: &Command, parsed: &mut ParsedArgs) -> Result<(), AppError> {
+        for flag in command.flags.values() {
+            let (Some(parser), Some(value)) = (&flag.value_parser, parsed.flags.get(&flag.name))
+            else {
+                continue;
+            };
+
+            let values = value_parser::raw_values(value)
+                .into_iter()
+                .map(|raw| {
+                    parser.parse(&raw).map_err(|message| AppError::InvalidFlagValue {
+                        flag: flag.name.clone(),
+                        value: raw,
+                        expected: message,
+                        suggestions: Vec::new(),
+                    })
+                })
+                .collect::<Result<Vec<_>, _>>()?;
+
+            parsed.typed_values.insert(flag.name.clone(), values);
+        }
+
         Ok(())
     }
+
+    fn read_env_value(
+        flag: &Flag,
+        env: &EnvSource,
+    ) -> Result<Option<(FlagValue, String)>, AppError> {
+        let Some(var) = &flag.env else {
+            return Ok(None);
+        };
+        let Some(raw_value) = env.get(var) else {
+            return Ok(None);
+        };
+
+        flag.parse_value(&raw_value)
+            .map(|value| Some((value, var.clone())))
+            .map_err(|error| AppError::InvalidEnvValue {
+                flag: flag.name.clone(),
+                var: var.clone(),
+                message: error.to_string(),
+            })
+    }
 }
 
 #[cfg(test)]
-mod tests;
+pub(crate) mod tests;
 
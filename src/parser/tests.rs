@@ -1,7 +1,15 @@
 use crate::flag::FlagValue;
-use crate::parser::CLIParser;
+use crate::parser::{CLIParser, ValueSource};
+use crate::command::PositionalArg;
+use crate::env::EnvSource;
+use crate::log_level::LogLevel;
 use crate::{AppError, Command, Flag, FlagType};
 
+/// Faz o parsing de `args` com `command`, sem precisar converter para `String`
+pub(crate) fn parse(command: &Command, args: &[&str]) -> Result<crate::ParsedArgs, AppError> {
+    CLIParser::parse(command, args.iter().map(|arg| arg.to_string()).collect())
+}
+
 #[test]
 fn test_parse_simple_command() {
     let command = Command::new("test").add_flag(Flag::new("name", FlagType::String).required(true));
@@ -43,6 +51,23 @@ fn test_help_requested() {
     assert!(result.unwrap().help_requested);
 }
 
+#[test]
+fn test_help_after_flag_missing_value() {
+    let command = Command::new("test")
+        .add_flag(Flag::new("name", FlagType::String).short('n').required(true))
+        .add_flag(Flag::new("tags", FlagType::StringList));
+
+    for args in [&["--name", "--help"][..], &["-n", "-h"], &["--tags", "--help"]] {
+        assert!(parse(&command, args).unwrap().help_requested);
+    }
+    assert_eq!(
+        parse(&command, &["--name", "--tags"]).unwrap_err(),
+        AppError::FlagValueMissing {
+            flag: "name".to_string()
+        }
+    );
+}
+
 #[test]
 fn test_missing_required_flag() {
     let command = Command::new("test")
@@ -71,7 +96,866 @@ fn test_unknown_flag() {
     assert_eq!(
         err,
         AppError::UnknownFlag {
-            flag: "unknown".to_string()
+            flag: "unknown".to_string(),
+            suggestions: vec![]
+        }
+    );
+}
+
+#[test]
+fn test_long_flag_with_attached_value() {
+    let command = Command::new("test").add_flag(Flag::new("output", FlagType::String));
+
+    let parsed = CLIParser::parse(&command, vec!["--output=json".to_string()]).unwrap();
+    assert_eq!(
+        parsed.get_flag("output").unwrap(),
+        &FlagValue::String("json".to_string())
+    );
+}
+
+#[test]
+fn test_attached_value_containing_equals() {
+    let command = Command::new("test")
+        .add_flag(Flag::new("define", FlagType::String).short('D'));
+
+    let parsed = CLIParser::parse(&command, vec!["--define=key=value".to_string()]).unwrap();
+    assert_eq!(
+        parsed.get_flag("define").unwrap(),
+        &FlagValue::String("key=value".to_string())
+    );
+
+    let parsed = CLIParser::parse(&command, vec!["-Dkey=value".to_string()]).unwrap();
+    assert_eq!(
+        parsed.get_flag("define").unwrap(),
+        &FlagValue::String("key=value".to_string())
+    );
+}
+
+#[test]
+fn test_short_flag_with_attached_value() {
+    let command = Command::new("test").add_flag(Flag::new("times", FlagType::Integer).short('n'));
+
+    let parsed = CLIParser::parse(&command, vec!["-n5".to_string()]).unwrap();
+    assert_eq!(parsed.get_flag("times").unwrap(), &FlagValue::Integer(5));
+
+    let parsed = CLIParser::parse(&command, vec!["-n=7".to_string()]).unwrap();
+    assert_eq!(parsed.get_flag("times").unwrap(), &FlagValue::Integer(7));
+}
+
+#[test]
+fn test_attached_value_on_bool_flag() {
+    let command = Command::new("test")
+        .show_help_on_empty(false)
+        .add_flag(Flag::new("verbose", FlagType::Bool).short('v'));
+
+    let parsed = parse(&command, &["--verbose=no"]).unwrap();
+    assert_eq!(parsed.get_flag("verbose"), Some(&FlagValue::Bool(false)));
+    let parsed = parse(&command, &["-v=yes"]).unwrap();
+    assert_eq!(parsed.get_flag("verbose"), Some(&FlagValue::Bool(true)));
+
+    assert!(matches!(
+        parse(&command, &["--verbose=talvez"]).unwrap_err(),
+        AppError::InvalidFlagValue { ref flag, ref value, .. } if flag == "verbose" && value == "talvez"
+    ));
+}
+
+#[test]
+fn test_negatable_flag_last_wins() {
+    let command = Command::new("app")
+        .show_help_on_empty(false)
+        .add_flag(Flag::new("color", FlagType::Bool).negatable(true).global(true))
+        .add_flag(Flag::new("cache", FlagType::Bool))
+        .add_subcommand(Command::new("run"));
+
+    let parsed = parse(&command, &["--no-color"]).unwrap();
+    assert_eq!(parsed.get_flag("color"), Some(&FlagValue::Bool(false)));
+    assert_eq!(parsed.value_source("color"), Some(&ValueSource::CommandLine));
+
+    let parsed = parse(&command, &["--no-color", "--color"]).unwrap();
+    assert_eq!(parsed.get_flag("color"), Some(&FlagValue::Bool(true)));
+    let parsed = parse(&command, &["--color", "run", "--no-color"]).unwrap();
+    assert_eq!(parsed.get_flag("color"), Some(&FlagValue::Bool(false)));
+
+    assert_eq!(
+        parse(&command, &["--no-color=true"]).unwrap_err(),
+        AppError::FlagDoesNotTakeValue {
+            flag: "no-color".to_string(),
+            value: "true".to_string()
+        }
+    );
+    assert!(matches!(
+        parse(&command, &["--no-cache"]),
+        Err(AppError::UnknownFlag { ref flag, .. }) if flag == "no-cache"
+    ));
+}
+
+#[test]
+fn test_negated_flag_matches_long_name_only() {
+    let command = Command::new("app")
+        .show_help_on_empty(false)
+        .add_flag(Flag::new("x", FlagType::Bool).negatable(true))
+        .add_flag(Flag::new("color", FlagType::Bool).short('c').negatable(true));
+
+    let parsed = parse(&command, &["-x", "--no-x"]).unwrap();
+    assert_eq!(parsed.get_flag("x"), Some(&FlagValue::Bool(false)));
+    assert!(matches!(
+        parse(&command, &["--no-c"]),
+        Err(AppError::UnknownFlag { ref flag, .. }) if flag == "no-c"
+    ));
+}
+
+#[test]
+fn test_negated_flag_requires_bool() {
+    let command = Command::new("app")
+        .show_help_on_empty(false)
+        .add_flag(Flag::new("color", FlagType::String).negatable(true));
+
+    assert!(matches!(
+        parse(&command, &["--no-color"]),
+        Err(AppError::UnknownFlag { ref flag, .. }) if flag == "no-color"
+    ));
+}
+
+#[test]
+fn test_attached_empty_value() {
+    let command = Command::new("test").add_flag(Flag::new("name", FlagType::String));
+
+    let parsed = CLIParser::parse(&command, vec!["--name=".to_string()]).unwrap();
+    assert_eq!(
+        parsed.get_flag("name").unwrap(),
+        &FlagValue::String(String::new())
+    );
+}
+
+fn cluster_command() -> Command {
+    Command::new("tar")
+        .add_flag(Flag::new("extract", FlagType::Bool).short('x'))
+        .add_flag(Flag::new("gzip", FlagType::Bool).short('z'))
+        .add_flag(Flag::new("verbose", FlagType::Bool).short('v'))
+        .add_flag(Flag::new("file", FlagType::String).short('f'))
+}
+
+#[test]
+fn test_combined_short_bool_flags() {
+    let parsed = CLIParser::parse(&cluster_command(), vec!["-xzv".to_string()]).unwrap();
+
+    assert_eq!(parsed.get_flag("extract").unwrap(), &FlagValue::Bool(true));
+    assert_eq!(parsed.get_flag("gzip").unwrap(), &FlagValue::Bool(true));
+    assert_eq!(parsed.get_flag("verbose").unwrap(), &FlagValue::Bool(true));
+    assert!(!parsed.has_flag("file"));
+}
+
+#[test]
+fn test_short_cluster_ending_in_valued_flag() {
+    let args = vec!["-xzf".to_string(), "archive.tar".to_string()];
+    let parsed = CLIParser::parse(&cluster_command(), args).unwrap();
+
+    assert_eq!(parsed.get_flag("extract").unwrap(), &FlagValue::Bool(true));
+    assert_eq!(parsed.get_flag("gzip").unwrap(), &FlagValue::Bool(true));
+    assert_eq!(
+        parsed.get_flag("file").unwrap(),
+        &FlagValue::String("archive.tar".to_string())
+    );
+}
+
+#[test]
+fn test_short_cluster_valued_flag_consumes_rest() {
+    let parsed = CLIParser::parse(&cluster_command(), vec!["-xfarchive.tar".to_string()]).unwrap();
+
+    assert_eq!(parsed.get_flag("extract").unwrap(), &FlagValue::Bool(true));
+    assert_eq!(
+        parsed.get_flag("file").unwrap(),
+        &FlagValue::String("archive.tar".to_string())
+    );
+    assert!(!parsed.has_flag("verbose"));
+}
+
+#[test]
+fn test_short_cluster_missing_value() {
+    let result = CLIParser::parse(&cluster_command(), vec!["-xf".to_string()]);
+    assert_eq!(
+        result.unwrap_err(),
+        AppError::FlagValueMissing {
+            flag: "file".to_string()
+        }
+    );
+}
+
+#[test]
+fn test_short_cluster_unknown_char() {
+    let result = CLIParser::parse(&cluster_command(), vec!["-xqz".to_string()]);
+    assert_eq!(
+        result.unwrap_err(),
+        AppError::UnknownFlagInCluster {
+            flag: 'q',
+            cluster: "xqz".to_string(),
+            position: 2
+        }
+    );
+}
+
+#[test]
+fn test_terminator_fills_positionals_then_trailing() {
+    let command = Command::new("run")
+        .add_flag(Flag::new("verbose", FlagType::Bool).short('v'))
+        .add_positional_arg(PositionalArg::new("file"));
+
+    let args = vec!["-v", "--", "-weird", "--release", "-x"]
+        .into_iter()
+        .map(String::from)
+        .collect();
+    let parsed = CLIParser::parse(&command, args).unwrap();
+
+    assert_eq!(parsed.get_flag("verbose").unwrap(), &FlagValue::Bool(true));
+    assert_eq!(parsed.positional_args, vec!["-weird".to_string()]);
+    assert_eq!(
+        parsed.trailing_args,
+        vec!["--release".to_string(), "-x".to_string()]
+    );
+}
+
+#[test]
+fn test_flag_value_starting_with_hyphen() {
+    let command = Command::new("test")
+        .add_flag(Flag::new("offset", FlagType::Integer).allow_hyphen_values(true))
+        .add_flag(Flag::new("name", FlagType::String));
+
+    let args = vec!["--offset".to_string(), "-5".to_string()];
+    let parsed = CLIParser::parse(&command, args).unwrap();
+    assert_eq!(parsed.get_flag("offset").unwrap(), &FlagValue::Integer(-5));
+
+    let args = vec!["--name".to_string(), "-5".to_string()];
+    let result = CLIParser::parse(&command, args);
+    assert_eq!(
+        result.unwrap_err(),
+        AppError::FlagValueMissing {
+            flag: "name".to_string()
+        }
+    );
+}
+
+#[test]
+fn test_positional_starting_with_hyphen() {
+    let command = Command::new("test")
+        .add_flag(Flag::new("verbose", FlagType::Bool).short('v'))
+        .add_positional_arg(PositionalArg::new("number").allow_hyphen_values(true));
+
+    let args = vec!["-v".to_string(), "-10".to_string()];
+    let parsed = CLIParser::parse(&command, args).unwrap();
+
+    assert_eq!(parsed.get_flag("verbose").unwrap(), &FlagValue::Bool(true));
+    assert_eq!(parsed.positional_args, vec!["-10".to_string()]);
+}
+
+#[test]
+fn test_nested_subcommand_tree() {
+    let command = Command::new("app")
+        .add_flag(Flag::new("numbers", FlagType::IntegerList))
+        .add_subcommand(
+            Command::new("calc").add_subcommand(
+                Command::new("add").add_flag(Flag::new("numbers", FlagType::IntegerList)),
+            ),
+        );
+
+    let args = vec!["--numbers", "9", "calc", "add", "--numbers", "1,2"]
+        .into_iter()
+        .map(String::from)
+        .collect();
+    let parsed = CLIParser::parse(&command, args).unwrap();
+
+    assert_eq!(parsed.command_path(), vec!["calc", "add"]);
+    assert_eq!(parsed.subcommand, Some("calc".to_string()));
+    assert_eq!(parsed.flags.get("numbers").unwrap(), &FlagValue::IntegerList(vec![9]));
+
+    let calc = parsed.subcommand_matches().unwrap();
+    assert_eq!(calc.command, "calc");
+    assert!(calc.flags.is_empty());
+
+    let add = calc.subcommand_matches().unwrap();
+    assert_eq!(add.command, "add");
+    assert_eq!(add.flags.get("numbers").unwrap(), &FlagValue::IntegerList(vec![1, 2]));
+    assert_eq!(parsed.deepest_matches().command, "add");
+
+    assert_eq!(
+        parsed.get_flag("numbers").unwrap(),
+        &FlagValue::IntegerList(vec![1, 2])
+    );
+}
+
+#[test]
+fn test_command_path_distinguishes_levels() {
+    let command = Command::new("app")
+        .add_subcommand(Command::new("add").show_help_on_empty(false))
+        .add_subcommand(
+            Command::new("calc").add_subcommand(Command::new("add").show_help_on_empty(false)),
+        );
+
+    let parsed = CLIParser::parse(&command, vec!["add".to_string()]).unwrap();
+    assert_eq!(parsed.command_path(), vec!["add"]);
+
+    let parsed = CLIParser::parse(&command, vec!["calc".to_string(), "add".to_string()]).unwrap();
+    assert_eq!(parsed.command_path(), vec!["calc", "add"]);
+}
+
+#[test]
+fn test_global_flag_accepted_in_subcommand() {
+    let command = Command::new("app")
+        .add_flag(Flag::new("verbose", FlagType::Bool).short('v').global(true))
+        .add_flag(
+            Flag::new("config", FlagType::String)
+                .global(true)
+                .default_value(FlagValue::String("default.toml".to_string())),
+        )
+        .add_subcommand(
+            Command::new("calc").add_subcommand(
+                Command::new("add").add_flag(Flag::new("numbers", FlagType::IntegerList)),
+            ),
+        );
+
+    let args = vec!["calc", "add", "-v", "--config", "custom.toml", "--numbers", "1"]
+        .into_iter()
+        .map(String::from)
+        .collect();
+    let parsed = CLIParser::parse(&command, args).unwrap();
+
+    assert_eq!(parsed.flags.get("verbose").unwrap(), &FlagValue::Bool(true));
+    assert_eq!(
+        parsed.flags.get("config").unwrap(),
+        &FlagValue::String("custom.toml".to_string())
+    );
+
+    let add = parsed.deepest_matches();
+    assert!(!add.flags.contains_key("verbose"));
+    assert!(!add.flags.contains_key("config"));
+    assert_eq!(add.flags.get("numbers").unwrap(), &FlagValue::IntegerList(vec![1]));
+}
+
+#[test]
+fn test_global_flag_required_satisfied_in_subcommand() {
+    let command = Command::new("app")
+        .add_flag(Flag::new("token", FlagType::String).global(true).required(true))
+        .add_subcommand(Command::new("deploy").show_help_on_empty(false));
+
+    let args = vec!["deploy".to_string(), "--token".to_string(), "abc".to_string()];
+    let parsed = CLIParser::parse(&command, args).unwrap();
+    assert_eq!(
+        parsed.flags.get("token").unwrap(),
+        &FlagValue::String("abc".to_string())
+    );
+
+    let result = CLIParser::parse(&command, vec!["deploy".to_string()]);
+    assert_eq!(
+        result.unwrap_err(),
+        AppError::RequiredFlagNotProvided {
+            flag: "token".to_string()
+        }
+    );
+}
+
+#[test]
+fn test_non_global_flag_not_accepted_in_subcommand() {
+    let command = Command::new("app")
+        .add_flag(Flag::new("local", FlagType::Bool))
+        .add_subcommand(Command::new("sub"));
+
+    let result = CLIParser::parse(&command, vec!["sub".to_string(), "--local".to_string()]);
+    assert_eq!(
+        result.unwrap_err(),
+        AppError::UnknownFlag {
+            flag: "local".to_string(),
+            suggestions: vec!["app --local".to_string()]
+        }
+    );
+}
+
+#[test]
+fn test_unknown_flag_suggestions() {
+    let command = Command::new("app")
+        .add_flag(Flag::new("verbose", FlagType::Bool).global(true))
+        .add_subcommand(Command::new("hello").add_flag(Flag::new("name", FlagType::String)));
+
+    let result = CLIParser::parse(&command, vec!["hello".to_string(), "--verbos".to_string()]);
+    assert_eq!(result.unwrap_err().suggestions(), &["--verbose".to_string()]);
+}
+
+#[test]
+fn test_unknown_flag_suggests_other_subcommand() {
+    let command = Command::new("app")
+        .add_subcommand(Command::new("hello").add_flag(Flag::new("name", FlagType::String)))
+        .add_subcommand(
+            Command::new("calc").add_subcommand(
+                Command::new("add").add_flag(Flag::new("numbers", FlagType::IntegerList)),
+            ),
+        );
+
+    let result = CLIParser::parse(&command, vec!["hello".to_string(), "--numbers".to_string()]);
+    assert_eq!(
+        result.unwrap_err().suggestions(),
+        &["app calc add --numbers".to_string()]
+    );
+}
+
+#[test]
+fn test_command_not_found_suggestions() {
+    let command = Command::new("app")
+        .add_subcommand(Command::new("hello"))
+        .add_subcommand(Command::new("help-me"))
+        .add_subcommand(Command::new("calc"));
+
+    let result = CLIParser::parse(&command, vec!["helo".to_string()]);
+    assert_eq!(
+        result.unwrap_err(),
+        AppError::CommandNotFound {
+            command: "helo".to_string(),
+            suggestions: vec!["hello".to_string()]
+        }
+    );
+}
+
+fn env_command() -> Command {
+    Command::new("app").show_help_on_empty(false).add_flag(
+        Flag::new("timeout", FlagType::Integer)
+            .env("APP_TIMEOUT")
+            .default_value(FlagValue::Integer(30)),
+    )
+}
+
+#[test]
+fn test_env_fallback_precedence() {
+    let env = EnvSource::from_pairs([("APP_TIMEOUT", "10")]);
+
+    let parsed = CLIParser::parse_with_env(&env_command(), vec![], &env).unwrap();
+    assert_eq!(parsed.get_flag("timeout").unwrap(), &FlagValue::Integer(10));
+
+    let args = vec!["--timeout".to_string(), "5".to_string()];
+    let parsed = CLIParser::parse_with_env(&env_command(), args, &env).unwrap();
+    assert_eq!(parsed.get_flag("timeout").unwrap(), &FlagValue::Integer(5));
+
+    let empty = EnvSource::from_pairs::<_, &str, &str>([]);
+    let parsed = CLIParser::parse_with_env(&env_command(), vec![], &empty).unwrap();
+    assert_eq!(parsed.get_flag("timeout").unwrap(), &FlagValue::Integer(30));
+}
+
+#[test]
+fn test_env_satisfies_required_flag() {
+    let command = Command::new("app")
+        .show_help_on_empty(false)
+        .add_flag(Flag::new("token", FlagType::String).env("APP_TOKEN").required(true));
+
+    let env = EnvSource::from_pairs([("APP_TOKEN", "secret")]);
+    let parsed = CLIParser::parse_with_env(&command, vec![], &env).unwrap();
+    assert_eq!(
+        parsed.get_flag("token").unwrap(),
+        &FlagValue::String("secret".to_string())
+    );
+
+    let env = EnvSource::from_pairs([("APP_TOKEN", "")]);
+    let result = CLIParser::parse_with_env(&command, vec![], &env);
+    assert_eq!(
+        result.unwrap_err(),
+        AppError::RequiredFlagNotProvided {
+            flag: "token".to_string()
+        }
+    );
+}
+
+#[test]
+fn test_invalid_env_value() {
+    let env = EnvSource::from_pairs([("APP_TIMEOUT", "soon")]);
+
+    let result = CLIParser::parse_with_env(&env_command(), vec![], &env);
+    assert!(matches!(
+        result.unwrap_err(),
+        AppError::InvalidEnvValue { flag, var, .. } if flag == "timeout" && var == "APP_TIMEOUT"
+    ));
+}
+
+#[test]
+fn test_value_sources() {
+    let command = Command::new("app")
+        .show_help_on_empty(false)
+        .add_flag(Flag::new("verbose", FlagType::Bool).global(true))
+        .add_flag(Flag::new("token", FlagType::String).env("APP_TOKEN"))
+        .add_flag(Flag::new("timeout", FlagType::Integer).default_value(FlagValue::Integer(30)))
+        .add_subcommand(Command::new("run").add_flag(Flag::new("name", FlagType::String)));
+
+    let env = EnvSource::from_pairs([("APP_TOKEN", "abc")]);
+    let args = vec!["run", "--name", "x", "--verbose"]
+        .into_iter()
+        .map(String::from)
+        .collect();
+    let parsed = CLIParser::parse_with_env(&command, args, &env).unwrap();
+
+    assert_eq!(parsed.value_source("name"), Some(&ValueSource::CommandLine));
+    assert_eq!(parsed.value_source("verbose"), Some(&ValueSource::CommandLine));
+    assert_eq!(
+        parsed.value_source("token"),
+        Some(&ValueSource::Environment("APP_TOKEN".to_string()))
+    );
+    assert_eq!(parsed.value_source("timeout"), Some(&ValueSource::Default));
+    assert_eq!(parsed.value_source("missing"), None);
+
+    assert_eq!(parsed.sources.len(), parsed.flags.len());
+    assert!(!parsed.deepest_matches().sources.contains_key("verbose"));
+}
+
+fn relationships_command() -> Command {
+    Command::new("export")
+        .show_help_on_empty(false)
+        .add_flag(Flag::new("json", FlagType::Bool).conflicts_with("table"))
+        .add_flag(Flag::new("table", FlagType::Bool))
+        .add_flag(Flag::new("user", FlagType::String).requires("password"))
+        .add_flag(Flag::new("password", FlagType::String))
+        .add_flag(Flag::new("output", FlagType::String).required_unless("stdout"))
+        .add_flag(Flag::new("stdout", FlagType::Bool))
+        .add_flag(
+            Flag::new("format", FlagType::String)
+                .conflicts_with("json")
+                .default_value(FlagValue::String("csv".to_string())),
+        )
+}
+
+#[test]
+fn test_conflicting_flags() {
+    let command = relationships_command();
+    assert_eq!(
+        parse(&command, &["--stdout", "--json", "--table"]).unwrap_err(),
+        AppError::ConflictingFlags {
+            flag: "json".to_string(),
+            other: "table".to_string()
+        }
+    );
+
+    // O valor padrão de --format não conta como conflito
+    assert!(parse(&command, &["--stdout", "--json"]).is_ok());
+    assert!(parse(&command, &["--stdout", "--format", "xml", "--json"]).is_err());
+}
+
+#[test]
+fn test_requires_and_required_unless() {
+    let command = relationships_command();
+    assert_eq!(
+        parse(&command, &["--stdout", "--user", "rafael"]).unwrap_err(),
+        AppError::MissingRequiredFlag {
+            flag: "user".to_string(),
+            required: "password".to_string()
         }
     );
+    assert!(parse(&command, &["--stdout", "--user", "rafael", "--password", "x"]).is_ok());
+
+    let error = parse(&command, &["--json"]).unwrap_err();
+    assert_eq!(
+        error,
+        AppError::RequiredUnlessNotProvided {
+            flag: "output".to_string(),
+            alternatives: vec!["stdout".to_string()]
+        }
+    );
+    assert_eq!(
+        error.to_string(),
+        "Flag obrigatória não fornecida: --output (ou --stdout)"
+    );
+    assert!(parse(&command, &["--output", "out.csv"]).is_ok());
+}
+
+#[test]
+fn test_argument_groups() {
+    use crate::command::{ArgGroup, GroupKind};
+
+    let command = Command::new("login")
+        .show_help_on_empty(false)
+        .add_flag(Flag::new("token", FlagType::String))
+        .add_flag(Flag::new("password", FlagType::String))
+        .add_flag(Flag::new("quiet", FlagType::Bool))
+        .add_flag(Flag::new("verbose", FlagType::Bool))
+        .add_group(
+            ArgGroup::new("auth", GroupKind::ExactlyOne)
+                .flag("token")
+                .flag("password"),
+        )
+        .add_group(
+            ArgGroup::new("output", GroupKind::AtMostOne)
+                .flag("quiet")
+                .flag("verbose"),
+        );
+
+    let error = parse(&command, &[]).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Grupo auth: informe exatamente uma de --token, --password"
+    );
+    assert_eq!(
+        parse(&command, &["--token", "t", "--password", "p"]).unwrap_err(),
+        AppError::GroupConflict {
+            group: "auth".to_string(),
+            flag: "token".to_string(),
+            other: "password".to_string()
+        }
+    );
+    assert!(matches!(
+        parse(&command, &["--token", "t", "--quiet", "--verbose"]),
+        Err(AppError::GroupConflict { ref group, .. }) if group == "output"
+    ));
+    assert!(parse(&command, &["--password", "p", "--quiet"]).is_ok());
+}
+
+fn count_command() -> Command {
+    Command::new("app")
+        .show_help_on_empty(false)
+        .add_flag(Flag::new("verbose", FlagType::Count).short('v').global(true))
+        .add_flag(
+            Flag::new("quiet", FlagType::Count)
+                .short('q')
+                .global(true)
+                .decrements("verbose"),
+        )
+        .add_flag(Flag::new("force", FlagType::Bool).short('f'))
+        .add_subcommand(Command::new("run").add_flag(Flag::new("name", FlagType::String).short('n')))
+}
+
+#[test]
+fn test_count_occurrences_in_clusters_and_subcommands() {
+    let command = count_command();
+    let parsed = parse(&command, &["-vfv", "run", "-vn", "x", "--verbose"]).unwrap();
+    assert_eq!(parsed.count("verbose"), 4);
+    assert_eq!(parsed.value_source("verbose"), Some(&ValueSource::CommandLine));
+    assert_eq!(parsed.log_level("verbose", LogLevel::Warn), LogLevel::Trace);
+    assert_eq!(parse(&command, &["run"]).unwrap().count("verbose"), 0);
+}
+
+#[test]
+fn test_negative_counter_offsets_level() {
+    let command = count_command();
+    let parsed = parse(&command, &["-vvv", "-q"]).unwrap();
+    assert_eq!(parsed.count("verbose"), 2);
+    assert_eq!(parsed.count("quiet"), 1);
+    assert_eq!(parsed.log_level("verbose", LogLevel::Warn), LogLevel::Debug);
+
+    let parsed = parse(&command, &["-qq"]).unwrap();
+    assert_eq!(parsed.count("verbose"), -2);
+    assert_eq!(parsed.log_level("verbose", LogLevel::Warn), LogLevel::Off);
+}
+
+#[test]
+fn test_counter_arithmetic_saturates() {
+    let command = count_command()
+        .add_flag(Flag::new("debug", FlagType::Count).env("APP_DEBUG"))
+        .add_flag(Flag::new("silent", FlagType::Count).env("APP_SILENT").decrements("debug"));
+
+    let max = i64::MAX.to_string();
+    let env = EnvSource::from_pairs([("APP_DEBUG", max.as_str())]);
+    let parsed = CLIParser::parse_with_env(&command, vec![], &env).unwrap();
+    assert_eq!(parsed.count("debug"), i64::MAX);
+    assert_eq!(parsed.log_level("debug", LogLevel::Warn), LogLevel::Trace);
+
+    let min = i64::MIN.to_string();
+    let env = EnvSource::from_pairs([("APP_DEBUG", min.as_str()), ("APP_SILENT", max.as_str())]);
+    let parsed = CLIParser::parse_with_env(&command, vec![], &env).unwrap();
+    assert_eq!(parsed.count("debug"), i64::MIN);
+    assert_eq!(parsed.log_level("debug", LogLevel::Warn), LogLevel::Off);
+
+    let env = EnvSource::from_pairs([("APP_SILENT", min.as_str())]);
+    let parsed = CLIParser::parse_with_env(&command, vec![], &env).unwrap();
+    assert_eq!(parsed.count("debug"), i64::MAX);
+}
+
+#[test]
+fn test_count_does_not_take_value() {
+    assert_eq!(
+        parse(&count_command(), &["--verbose=2"]).unwrap_err(),
+        AppError::FlagDoesNotTakeValue {
+            flag: "verbose".to_string(),
+            value: "2".to_string()
+        }
+    );
+}
+
+#[test]
+fn test_optional_value_flag() {
+    let command = Command::new("ls")
+        .show_help_on_empty(false)
+        .add_flag(
+            Flag::new("color", FlagType::String)
+                .possible_values(vec!["auto".to_string(), "always".to_string(), "never".to_string()])
+                .optional_value(FlagValue::String("auto".to_string()))
+                .default_value(FlagValue::String("never".to_string())),
+        )
+        .add_flag(Flag::new("all", FlagType::Bool).short('a'))
+        .add_positional_arg(PositionalArg::new("path").required(false))
+        .add_subcommand(Command::new("tree"));
+    let color = |parsed: &crate::ParsedArgs| parsed.get_flag("color").cloned();
+
+    let parsed = parse(&command, &[]).unwrap();
+    assert_eq!(color(&parsed), Some(FlagValue::String("never".to_string())));
+    assert_eq!(parsed.value_source("color"), Some(&ValueSource::Default));
+
+    let parsed = parse(&command, &["--color"]).unwrap();
+    assert_eq!(color(&parsed), Some(FlagValue::String("auto".to_string())));
+    assert_eq!(parsed.value_source("color"), Some(&ValueSource::CommandLine));
+
+    let parsed = parse(&command, &["--color", "-a"]).unwrap();
+    assert_eq!(color(&parsed), Some(FlagValue::String("auto".to_string())));
+    assert!(parsed.has_flag("all"));
+
+    let parsed = parse(&command, &["--color=always"]).unwrap();
+    assert_eq!(color(&parsed), Some(FlagValue::String("always".to_string())));
+    let parsed = parse(&command, &["--color", "always"]).unwrap();
+    assert_eq!(color(&parsed), Some(FlagValue::String("always".to_string())));
+
+    let parsed = parse(&command, &["--color", "tree"]).unwrap();
+    assert_eq!(color(&parsed), Some(FlagValue::String("auto".to_string())));
+    assert_eq!(parsed.command_path(), vec!["tree"]);
+
+    let parsed = parse(&command, &["--color", "--", "src"]).unwrap();
+    assert_eq!(color(&parsed), Some(FlagValue::String("auto".to_string())));
+    assert_eq!(parsed.get_args(), &vec!["src".to_string()]);
+}
+
+fn list_command() -> Command {
+    Command::new("app")
+        .add_flag(Flag::new("tags", FlagType::StringList).short('t'))
+        .add_flag(Flag::new("point", FlagType::IntegerList).num_values(2..=2))
+        .add_flag(
+            Flag::new("offsets", FlagType::IntegerList)
+                .allow_hyphen_values(true)
+                .value_delimiter(None),
+        )
+        .add_flag(Flag::new("verbose", FlagType::Bool).short('v'))
+        .add_positional_arg(PositionalArg::new("file").required(false))
+        .add_subcommand(Command::new("run"))
+}
+
+#[test]
+fn test_list_flag_consumes_multiple_values() {
+    let command = list_command();
+    let strings = |values: &[&str]| FlagValue::StringList(values.iter().map(|v| v.to_string()).collect());
+
+    let parsed = parse(&command, &["--tags", "a", "b,c", "-v", "-t", "d"]).unwrap();
+    assert_eq!(parsed.get_flag("tags"), Some(&strings(&["a", "b", "c", "d"])));
+    assert!(parsed.has_flag("verbose"));
+
+    // Para no subcomando e no `--`
+    let parsed = parse(&command, &["--tags", "a", "run"]).unwrap();
+    assert_eq!(parsed.get_flag("tags"), Some(&strings(&["a"])));
+    assert_eq!(parsed.command_path(), vec!["run"]);
+
+    let parsed = parse(&command, &["--tags", "a", "--", "arquivo"]).unwrap();
+    assert_eq!(parsed.get_flag("tags"), Some(&strings(&["a"])));
+    assert_eq!(parsed.get_args(), &vec!["arquivo".to_string()]);
+
+    // Valor anexado não consome os argumentos seguintes
+    let parsed = parse(&command, &["--tags=a,b", "arquivo"]).unwrap();
+    assert_eq!(parsed.get_flag("tags"), Some(&strings(&["a", "b"])));
+    assert_eq!(parsed.get_args(), &vec!["arquivo".to_string()]);
+}
+
+#[test]
+fn test_list_flag_before_positional() {
+    let add = |numbers: Flag| {
+        Command::new("add")
+            .add_flag(numbers)
+            .add_positional_arg(PositionalArg::new("label").required(false))
+    };
+    let command = add(Flag::new("numbers", FlagType::IntegerList));
+    let limited = add(Flag::new("numbers", FlagType::IntegerList).num_values(1..=1));
+
+    // Antes do consumo guloso, `x` era o argumento posicional
+    assert!(matches!(
+        parse(&command, &["--numbers", "1", "x"]),
+        Err(AppError::InvalidFlagValue { ref flag, ref value, .. }) if flag == "numbers" && value == "x"
+    ));
+
+    for (command, args) in [
+        (&command, &["--numbers=1", "x"][..]),
+        (&command, &["--numbers", "1", "--", "x"][..]),
+        (&limited, &["--numbers", "1", "x"][..]),
+    ] {
+        let parsed = parse(command, args).unwrap();
+        assert_eq!(parsed.get_flag("numbers"), Some(&FlagValue::IntegerList(vec![1])));
+        assert_eq!(parsed.get_args(), &vec!["x".to_string()]);
+    }
+}
+
+#[test]
+fn test_list_flag_leaves_required_positionals() {
+    let command = Command::new("app").add_subcommand(
+        Command::new("sub")
+            .add_flag(Flag::new("tags", FlagType::StringList))
+            .add_flag(Flag::new("name", FlagType::String))
+            .add_positional_arg(PositionalArg::new("file")),
+    );
+    let strings = |values: &[&str]| FlagValue::StringList(values.iter().map(|v| v.to_string()).collect());
+
+    for args in [
+        &["sub", "--tags", "a", "b", "file.txt"][..],
+        &["sub", "--tags", "a", "b", "file.txt", "--name", "x"][..],
+        &["sub", "--tags", "a", "b", "--", "file.txt"][..],
+    ] {
+        let parsed = parse(&command, args).unwrap();
+        let sub = parsed.subcommand_matches().unwrap();
+        assert_eq!(sub.get_flag("tags"), Some(&strings(&["a", "b"])));
+        assert_eq!(sub.get_args(), &vec!["file.txt".to_string()]);
+    }
+
+    // Um valor é sempre consumido, mesmo que falte o argumento posicional
+    assert!(matches!(
+        parse(&command, &["sub", "--tags", "a"]),
+        Err(AppError::NotEnoughArguments { .. })
+    ));
+}
+
+#[test]
+fn test_string_list_splits_on_delimiter() {
+    let strings = |values: &[&str]| FlagValue::StringList(values.iter().map(|v| v.to_string()).collect());
+    let command = Command::new("app")
+        .show_help_on_empty(false)
+        .add_flag(Flag::new("msg", FlagType::StringList).env("APP_MSG"))
+        .add_flag(Flag::new("raw", FlagType::StringList).env("APP_RAW").value_delimiter(None));
+
+    // Antes da divisão em `,`, cada valor era um único elemento
+    let parsed = parse(&command, &["--msg", "a, b", "--raw", "a, b"]).unwrap();
+    assert_eq!(parsed.get_flag("msg"), Some(&strings(&["a", " b"])));
+    assert_eq!(parsed.get_flag("raw"), Some(&strings(&["a, b"])));
+
+    let env = EnvSource::from_pairs([("APP_MSG", "a,b"), ("APP_RAW", "a,b")]);
+    let parsed = CLIParser::parse_with_env(&command, vec![], &env).unwrap();
+    assert_eq!(parsed.get_flag("msg"), Some(&strings(&["a", "b"])));
+    assert_eq!(parsed.get_flag("raw"), Some(&strings(&["a,b"])));
+}
+
+#[test]
+fn test_list_flag_num_values() {
+    let command = list_command();
+    let parsed = parse(&command, &["--point", "1", "2", "arquivo"]).unwrap();
+    assert_eq!(parsed.get_flag("point"), Some(&FlagValue::IntegerList(vec![1, 2])));
+    assert_eq!(parsed.get_args(), &vec!["arquivo".to_string()]);
+
+    let parsed = parse(&command, &["--point", "1,2"]).unwrap();
+    assert_eq!(parsed.get_flag("point"), Some(&FlagValue::IntegerList(vec![1, 2])));
+
+    let error = parse(&command, &["--point", "1", "-v"]).unwrap_err();
+    assert_eq!(
+        error,
+        AppError::InvalidValueCount {
+            flag: "point".to_string(),
+            expected: "exatamente 2".to_string(),
+            received: 1
+        }
+    );
+    assert_eq!(
+        error.to_string(),
+        "Número de valores inválido para --point: esperado exatamente 2, recebido 1"
+    );
+    assert!(matches!(
+        parse(&command, &["--point=1,2,3"]),
+        Err(AppError::InvalidValueCount { received: 3, .. })
+    ));
+}
+
+#[test]
+fn test_list_flag_with_hyphen_values_stops_at_known_flags() {
+    let command = list_command();
+    let parsed = parse(&command, &["--offsets", "-1", "-2", "-v"]).unwrap();
+    assert_eq!(parsed.get_flag("offsets"), Some(&FlagValue::IntegerList(vec![-1, -2])));
+    assert!(parsed.has_flag("verbose"));
 }
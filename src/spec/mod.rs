@@ -0,0 +1,520 @@
+use crate::command::{ArgGroup, Example, GroupKind, PositionalArg};
+use crate::config::{ConfigFormat, to_flag_value};
+use crate::{App, AppError, Command, Flag, FlagType};
+use serde::{Serialize, Serializer};
+use serde_json::{Map, Value};
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+/// Versão do formato da especificação. Muda quando um campo é removido ou muda
+/// de significado; campos novos não alteram a versão
+pub const SPEC_VERSION: u32 = 1;
+
+/// Especificação completa de uma `App`, serializada como:
+///
+/// ```json
+/// {
+///   "spec_version": 1,
+///   "name": "app",
+///   "version": "1.0.0",
+///   "description": "",
+///   "flags": { "verbose": { "short": "v", "type": "bool", ... } },
+///   "positional_args": [],
+///   "commands": { "calc": { "flags": {}, "commands": {}, ... } },
+///   ...
+/// }
+/// ```
+///
+/// Flags e comandos são indexados pelo nome e ordenados, para que a saída seja estável
+#[derive(Debug, Serialize)]
+pub struct AppSpec<'a> {
+    pub spec_version: u32,
+    pub name: &'a str,
+    pub version: &'a str,
+    pub description: &'a str,
+    pub flags: BTreeMap<&'a str, &'a Flag>,
+    pub positional_args: &'a [PositionalArg],
+    pub commands: BTreeMap<&'a str, &'a Command>,
+    pub show_help_on_empty: bool,
+    pub examples: &'a [Example],
+    pub groups: &'a [ArgGroup],
+}
+
+impl<'a> AppSpec<'a> {
+    pub fn new(app: &'a App) -> Self {
+        let root = &app.root_command;
+
+        Self {
+            spec_version: SPEC_VERSION,
+            name: &app.name,
+            version: &app.version,
+            description: &app.description,
+            flags: root.flags.iter().map(|(name, flag)| (name.as_str(), flag)).collect(),
+            positional_args: &root.positional_args,
+            commands: root
+                .subcommands
+                .iter()
+                .map(|(name, command)| (name.as_str(), command))
+                .collect(),
+            show_help_on_empty: root.show_help_on_empty,
+            examples: &root.examples,
+            groups: &root.groups,
+        }
+    }
+}
+
+/// Serializa a especificação de `app` em JSON formatado
+pub fn to_json(app: &App) -> String {
+    serde_json::to_string_pretty(&AppSpec::new(app))
+        .expect("a especificação contém apenas tipos serializáveis em JSON")
+}
+
+/// Monta uma `App` a partir de uma especificação no formato de `AppSpec`, em JSON,
+/// TOML ou YAML. Apenas `name` é obrigatório no topo e `type` em cada flag; os
+/// demais campos usam os mesmos padrões dos builders. Erros citam o caminho do
+/// item na especificação, ex: `commands.calc.flags.numbers: tipo desconhecido`
+pub fn from_str(contents: &str, format: ConfigFormat) -> Result<App, AppError> {
+    let value: Result<Value, String> = match format {
+        ConfigFormat::Toml => toml::from_str(contents).map_err(|e| e.to_string()),
+        ConfigFormat::Json => serde_json::from_str(contents).map_err(|e| e.to_string()),
+        ConfigFormat::Yaml => serde_yaml::from_str(contents).map_err(|e| e.to_string()),
+    };
+
+    let value = value.map_err(|message| AppError::ConfigurationError {
+        message: format!("especificação inválida: {}", message),
+    })?;
+
+    let app = build_app(&value)?;
+
+    app.validate_with_path()
+        .map_err(|(path, message)| spec_error(&path, message))?;
+
+    Ok(app)
+}
+
+/// Igual a `from_str`, lendo o arquivo e escolhendo o formato pela extensão
+pub fn from_file(path: impl AsRef<Path>) -> Result<App, AppError> {
+    let path = path.as_ref();
+
+    let format = ConfigFormat::from_path(path).ok_or_else(|| AppError::ConfigurationError {
+        message: format!(
+            "{}: extensão não suportada, use .toml, .json, .yaml ou .yml",
+            path.display()
+        ),
+    })?;
+
+    let contents = std::fs::read_to_string(path).map_err(|e| AppError::ConfigurationError {
+        message: format!("{}: {}", path.display(), e),
+    })?;
+
+    from_str(&contents, format).map_err(|error| match error {
+        AppError::ConfigurationError { message } => AppError::ConfigurationError {
+            message: format!("{}: {}", path.display(), message),
+        },
+        error => error,
+    })
+}
+
+fn spec_error(path: &str, message: impl Into<String>) -> AppError {
+    let message = message.into();
+
+    AppError::ConfigurationError {
+        message: if path.is_empty() {
+            message
+        } else {
+            format!("{}: {}", path, message)
+        },
+    }
+}
+
+fn child_path(parent: &str, key: &str) -> String {
+    if parent.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", parent, key)
+    }
+}
+
+/// Campos de um objeto da especificação, com o caminho usado nas mensagens de erro
+struct Fields<'a> {
+    path: String,
+    map: &'a Map<String, Value>,
+}
+
+impl<'a> Fields<'a> {
+    fn new(path: String, value: &'a Value, allowed: &[&str]) -> Result<Self, AppError> {
+        let map = value
+            .as_object()
+            .ok_or_else(|| spec_error(&path, "esperado um objeto"))?;
+
+        if let Some(key) = map.keys().find(|key| !allowed.contains(&key.as_str())) {
+            return Err(spec_error(&child_path(&path, key), "campo desconhecido"));
+        }
+
+        Ok(Self { path, map })
+    }
+
+    /// Valor do campo, tratando `null` como ausente
+    fn get(&self, key: &str) -> Option<&'a Value> {
+        self.map.get(key).filter(|value| !value.is_null())
+    }
+
+    fn error(&self, key: &str, message: impl Into<String>) -> AppError {
+        spec_error(&child_path(&self.path, key), message)
+    }
+
+    fn string(&self, key: &str) -> Result<Option<String>, AppError> {
+        self.get(key)
+            .map(|value| {
+                value
+                    .as_str()
+                    .map(str::to_string)
+                    .ok_or_else(|| self.error(key, "esperado uma string"))
+            })
+            .transpose()
+    }
+
+    fn usize(&self, key: &str) -> Result<Option<usize>, AppError> {
+        self.get(key)
+            .map(|value| {
+                value
+                    .as_u64()
+                    .and_then(|number| usize::try_from(number).ok())
+                    .ok_or_else(|| self.error(key, "esperado um inteiro não negativo"))
+            })
+            .transpose()
+    }
+
+    fn bool(&self, key: &str) -> Result<Option<bool>, AppError> {
+        self.get(key)
+            .map(|value| value.as_bool().ok_or_else(|| self.error(key, "esperado um boolean")))
+            .transpose()
+    }
+
+    fn object(&self, key: &str) -> Result<Option<&'a Map<String, Value>>, AppError> {
+        self.get(key)
+            .map(|value| value.as_object().ok_or_else(|| self.error(key, "esperado um objeto")))
+            .transpose()
+    }
+
+    fn string_list(&self, key: &str) -> Result<Option<Vec<String>>, AppError> {
+        self.get(key)
+            .map(|value| {
+                value
+                    .as_array()
+                    .and_then(|items| {
+                        items
+                            .iter()
+                            .map(|item| item.as_str().map(str::to_string))
+                            .collect::<Option<Vec<String>>>()
+                    })
+                    .ok_or_else(|| self.error(key, "esperado uma lista de strings"))
+            })
+            .transpose()
+    }
+
+    fn array(&self, key: &str) -> Result<&'a [Value], AppError> {
+        match self.get(key) {
+            Some(value) => value
+                .as_array()
+                .map(Vec::as_slice)
+                .ok_or_else(|| self.error(key, "esperado uma lista")),
+            None => Ok(&[]),
+        }
+    }
+}
+
+const APP_FIELDS: &[&str] = &[
+    "spec_version",
+    "name",
+    "version",
+    "description",
+    "flags",
+    "positional_args",
+    "commands",
+    "show_help_on_empty",
+    "examples",
+    "groups",
+];
+const COMMAND_FIELDS: &[&str] = &[
+    "description",
+    "flags",
+    "positional_args",
+    "commands",
+    "show_help_on_empty",
+    "hidden",
+    "examples",
+    "groups",
+];
+const FLAG_FIELDS: &[&str] = &[
+    "short",
+    "type",
+    "description",
+    "required",
+    "default",
+    "default_missing",
+    "possible_values",
+    "allow_hyphen_values",
+    "num_values",
+    "value_delimiter",
+    "global",
+    "negatable",
+    "env",
+    "conflicts_with",
+    "requires",
+    "required_unless",
+    "decrements",
+];
+const POSITIONAL_FIELDS: &[&str] = &["name", "description", "required", "allow_hyphen_values"];
+const EXAMPLE_FIELDS: &[&str] = &["command", "description"];
+const GROUP_FIELDS: &[&str] = &["name", "kind", "flags"];
+const VALUE_RANGE_FIELDS: &[&str] = &["min", "max"];
+
+fn build_app(value: &Value) -> Result<App, AppError> {
+    let fields = Fields::new(String::new(), value, APP_FIELDS)?;
+
+    if let Some(version) = fields.get("spec_version") {
+        match version.as_u64() {
+            Some(version) if version <= SPEC_VERSION as u64 => {}
+            _ => {
+                return Err(fields.error(
+                    "spec_version",
+                    format!("versão não suportada, esperado até {}", SPEC_VERSION),
+                ));
+            }
+        }
+    }
+
+    let name = fields
+        .string("name")?
+        .ok_or_else(|| fields.error("name", "campo obrigatório"))?;
+    let version = fields.string("version")?.unwrap_or_else(|| "0.0.0".to_string());
+
+    let mut app = App::new(name.clone(), version);
+    app.description = fields.string("description")?.unwrap_or_default();
+    app.root_command = build_command_fields(Command::new(name), &fields)?;
+
+    Ok(app)
+}
+
+fn build_command(name: &str, value: &Value, path: String) -> Result<Command, AppError> {
+    let fields = Fields::new(path, value, COMMAND_FIELDS)?;
+
+    let mut command = Command::new(name);
+    if let Some(description) = fields.string("description")? {
+        command = command.description(description);
+    }
+    if let Some(hidden) = fields.bool("hidden")? {
+        command = command.hidden(hidden);
+    }
+
+    build_command_fields(command, &fields)
+}
+
+/// Campos comuns ao topo da especificação e aos comandos
+fn build_command_fields(mut command: Command, fields: &Fields) -> Result<Command, AppError> {
+    if let Some(show) = fields.bool("show_help_on_empty")? {
+        command = command.show_help_on_empty(show);
+    }
+
+    if let Some(flags) = fields.object("flags")? {
+        for (name, value) in flags {
+            let path = child_path(&child_path(&fields.path, "flags"), name);
+            command = command.add_flag(build_flag(name, value, path)?);
+        }
+    }
+
+    for (index, value) in fields.array("positional_args")?.iter().enumerate() {
+        let path = format!("{}[{}]", child_path(&fields.path, "positional_args"), index);
+        command = command.add_positional_arg(build_positional(value, path)?);
+    }
+
+    for (index, value) in fields.array("examples")?.iter().enumerate() {
+        let path = format!("{}[{}]", child_path(&fields.path, "examples"), index);
+        let example = Fields::new(path, value, EXAMPLE_FIELDS)?;
+        let example_command = example
+            .string("command")?
+            .ok_or_else(|| example.error("command", "campo obrigatório"))?;
+
+        command = command.example(example_command, example.string("description")?.unwrap_or_default());
+    }
+
+    for (index, value) in fields.array("groups")?.iter().enumerate() {
+        let path = format!("{}[{}]", child_path(&fields.path, "groups"), index);
+        command = command.add_group(build_group(value, path)?);
+    }
+
+    if let Some(commands) = fields.object("commands")? {
+        for (name, value) in commands {
+            let path = child_path(&child_path(&fields.path, "commands"), name);
+            command = command.add_subcommand(build_command(name, value, path)?);
+        }
+    }
+
+    Ok(command)
+}
+
+fn parse_flag_type(name: &str) -> Option<FlagType> {
+    match name {
+        "bool" => Some(FlagType::Bool),
+        "string" => Some(FlagType::String),
+        "integer" => Some(FlagType::Integer),
+        "float" => Some(FlagType::Float),
+        "string_list" => Some(FlagType::StringList),
+        "integer_list" => Some(FlagType::IntegerList),
+        "count" => Some(FlagType::Count),
+        _ => None,
+    }
+}
+
+fn build_flag(name: &str, value: &Value, path: String) -> Result<Flag, AppError> {
+    let fields = Fields::new(path, value, FLAG_FIELDS)?;
+
+    let type_name = fields
+        .string("type")?
+        .ok_or_else(|| fields.error("type", "campo obrigatório"))?;
+    let flag_type = parse_flag_type(&type_name).ok_or_else(|| {
+        fields.error(
+            "type",
+            format!(
+                "tipo desconhecido '{}', use bool, string, integer, float, string_list, integer_list ou count",
+                type_name
+            ),
+        )
+    })?;
+
+    let mut flag = Flag::new(name, flag_type);
+
+    if let Some(short) = fields.string("short")? {
+        let mut chars = short.chars();
+        match (chars.next(), chars.next()) {
+            (Some(short), None) => flag = flag.short(short),
+            _ => return Err(fields.error("short", "esperado um único caractere")),
+        }
+    }
+
+    if let Some(description) = fields.string("description")? {
+        flag = flag.description(description);
+    }
+    if let Some(required) = fields.bool("required")? {
+        flag = flag.required(required);
+    }
+    if let Some(allow) = fields.bool("allow_hyphen_values")? {
+        flag = flag.allow_hyphen_values(allow);
+    }
+    if let Some(num_values) = fields.get("num_values") {
+        let range = Fields::new(child_path(&fields.path, "num_values"), num_values, VALUE_RANGE_FIELDS)?;
+        let min = range.usize("min")?.unwrap_or(0);
+        flag = match range.usize("max")? {
+            Some(max) => flag.num_values(min..=max),
+            None => flag.num_values(min..),
+        };
+    }
+    // `null` desativa o separador padrão das listas, então não é tratado como ausente
+    match fields.map.get("value_delimiter") {
+        Some(Value::Null) => flag = flag.value_delimiter(None),
+        Some(_) => {
+            let delimiter = fields.string("value_delimiter")?.unwrap_or_default();
+            let mut chars = delimiter.chars();
+            match (chars.next(), chars.next()) {
+                (Some(delimiter), None) => flag = flag.value_delimiter(Some(delimiter)),
+                _ => return Err(fields.error("value_delimiter", "esperado um único caractere")),
+            }
+        }
+        None => {}
+    }
+    if let Some(global) = fields.bool("global")? {
+        flag = flag.global(global);
+    }
+    if let Some(negatable) = fields.bool("negatable")? {
+        flag = flag.negatable(negatable);
+    }
+    if let Some(env) = fields.string("env")? {
+        flag = flag.env(env);
+    }
+
+    if let Some(values) = fields.string_list("possible_values")? {
+        flag = flag.possible_values(values);
+    }
+
+    flag.conflicts_with = fields.string_list("conflicts_with")?.unwrap_or_default();
+    flag.requires = fields.string_list("requires")?.unwrap_or_default();
+    flag.required_unless = fields.string_list("required_unless")?.unwrap_or_default();
+    flag.decrements = fields.string("decrements")?;
+
+    // O padrão é convertido depois dos valores possíveis, para ser validado contra eles
+    if let Some(default) = fields.get("default") {
+        let value = to_flag_value(&flag, default).map_err(|message| fields.error("default", message))?;
+        flag = flag.default_value(value);
+    }
+    if let Some(default_missing) = fields.get("default_missing") {
+        let value = to_flag_value(&flag, default_missing)
+            .map_err(|message| fields.error("default_missing", message))?;
+        flag = flag.optional_value(value);
+    }
+
+    Ok(flag)
+}
+
+fn build_group(value: &Value, path: String) -> Result<ArgGroup, AppError> {
+    let fields = Fields::new(path, value, GROUP_FIELDS)?;
+
+    let name = fields
+        .string("name")?
+        .ok_or_else(|| fields.error("name", "campo obrigatório"))?;
+    let kind_name = fields
+        .string("kind")?
+        .ok_or_else(|| fields.error("kind", "campo obrigatório"))?;
+    let kind = match kind_name.as_str() {
+        "exactly_one" => GroupKind::ExactlyOne,
+        "at_least_one" => GroupKind::AtLeastOne,
+        "at_most_one" => GroupKind::AtMostOne,
+        _ => {
+            return Err(fields.error(
+                "kind",
+                format!(
+                    "tipo de grupo desconhecido '{}', use exactly_one, at_least_one ou at_most_one",
+                    kind_name
+                ),
+            ));
+        }
+    };
+
+    let mut group = ArgGroup::new(name, kind);
+    group.flags = fields.string_list("flags")?.unwrap_or_default();
+
+    Ok(group)
+}
+
+fn build_positional(value: &Value, path: String) -> Result<PositionalArg, AppError> {
+    let fields = Fields::new(path, value, POSITIONAL_FIELDS)?;
+
+    let name = fields
+        .string("name")?
+        .ok_or_else(|| fields.error("name", "campo obrigatório"))?;
+
+    let mut arg = PositionalArg::new(name);
+    if let Some(description) = fields.string("description")? {
+        arg = arg.description(description);
+    }
+    if let Some(required) = fields.bool("required")? {
+        arg = arg.required(required);
+    }
+    if let Some(allow) = fields.bool("allow_hyphen_values")? {
+        arg = arg.allow_hyphen_values(allow);
+    }
+
+    Ok(arg)
+}
+
+/// Serializa um `HashMap` com as chaves em ordem alfabética
+pub(crate) fn sorted_map<S, V>(map: &HashMap<String, V>, serializer: S) -> Result<S::Ok, S::Error>
+where
+    S: Serializer,
+    V: Serialize,
+{
+    map.iter().collect::<BTreeMap<_, _>>().serialize(serializer)
+}
+
+#[cfg(test)]
+mod tests;
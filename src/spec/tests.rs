@@ -0,0 +1,255 @@
+use super::*;
+use crate::flag::FlagValue;
+use crate::{Candidate, FlagType};
+use crate::config::ConfigFormat;
+use crate::test_utils::TempDir;
+use serde_json::{Value, json};
+
+fn test_app() -> App {
+    App::new("app", "1.0.0")
+        .description("Aplicação de teste")
+        .add_global_flag(Flag::new("verbose", FlagType::Bool).short('v'))
+        .add_global_flag(Flag::new("debug", FlagType::Count).short('d'))
+        .add_global_flag(Flag::new("quiet", FlagType::Count).short('q').decrements("debug"))
+        .add_command(
+            Command::new("calc").description("Calculadora").add_subcommand(
+                Command::new("add")
+                    .add_flag(
+                        Flag::new("numbers", FlagType::IntegerList)
+                            .env("APP_NUMBERS")
+                            .num_values(1..=3)
+                            .default_value(FlagValue::IntegerList(vec![1, 2]))
+                            .value_completer(|_| vec![Candidate::new("1")]),
+                    )
+                    .add_flag(Flag::new("tags", FlagType::StringList).value_delimiter(None))
+                    .add_flag(Flag::new("json", FlagType::Bool).conflicts_with("table"))
+                    .add_flag(Flag::new("table", FlagType::Bool))
+                    .add_group(ArgGroup::new("formato", GroupKind::AtMostOne).flag("json").flag("table"))
+                    .add_positional_arg(PositionalArg::new("label").required(false))
+                    .example("app calc add --numbers 3", ""),
+            ),
+        )
+}
+
+fn spec(app: &App) -> Value {
+    serde_json::from_str(&app.to_spec_json()).unwrap()
+}
+
+#[test]
+fn test_spec_header() {
+    let spec = spec(&test_app());
+
+    assert_eq!(spec["spec_version"], json!(SPEC_VERSION));
+    assert_eq!(spec["name"], json!("app"));
+    assert_eq!(spec["version"], json!("1.0.0"));
+    assert_eq!(spec["description"], json!("Aplicação de teste"));
+}
+
+#[test]
+fn test_spec_flag_attributes() {
+    let spec = spec(&test_app());
+
+    assert_eq!(
+        spec["flags"]["verbose"],
+        json!({
+            "short": "v",
+            "type": "bool",
+            "description": "",
+            "required": false,
+            "default": null,
+            "default_missing": null,
+            "possible_values": null,
+            "allow_hyphen_values": false,
+            "num_values": null,
+            "value_delimiter": null,
+            "global": true,
+            "negatable": false,
+            "env": null,
+            "conflicts_with": [],
+            "requires": [],
+            "required_unless": [],
+            "decrements": null
+        })
+    );
+
+    let numbers = &spec["commands"]["calc"]["commands"]["add"]["flags"]["numbers"];
+    assert_eq!(numbers["type"], json!("integer_list"));
+    assert_eq!(numbers["default"], json!([1, 2]));
+    assert_eq!(numbers["env"], json!("APP_NUMBERS"));
+    assert_eq!(numbers["num_values"], json!({ "min": 1, "max": 3 }));
+    assert_eq!(numbers["value_delimiter"], json!(","));
+
+    let tags = &spec["commands"]["calc"]["commands"]["add"]["flags"]["tags"];
+    assert_eq!(tags["num_values"], json!(null));
+    assert_eq!(tags["value_delimiter"], json!(null));
+
+    assert_eq!(spec["flags"]["debug"]["type"], json!("count"));
+    assert_eq!(spec["flags"]["quiet"]["decrements"], json!("debug"));
+}
+
+#[test]
+fn test_spec_command_tree() {
+    let spec = spec(&test_app());
+    let add = &spec["commands"]["calc"]["commands"]["add"];
+
+    assert_eq!(spec["commands"]["calc"]["description"], json!("Calculadora"));
+    assert_eq!(
+        add["positional_args"],
+        json!([{ "name": "label", "description": "", "required": false, "allow_hyphen_values": false }])
+    );
+    assert_eq!(add["examples"], json!([{ "command": "app calc add --numbers 3", "description": "" }]));
+    assert_eq!(add["hidden"], json!(false));
+    assert_eq!(
+        add["groups"],
+        json!([{ "name": "formato", "kind": "at_most_one", "flags": ["json", "table"] }])
+    );
+    assert_eq!(add["flags"]["json"]["conflicts_with"], json!(["table"]));
+}
+
+#[test]
+fn test_spec_is_stable() {
+    let app = test_app()
+        .add_command(Command::new("b"))
+        .add_command(Command::new("a"))
+        .add_command(Command::new("c"));
+
+    let json = app.to_spec_json();
+    assert_eq!(json, app.to_spec_json());
+
+    let a = json.find("\"a\":").unwrap();
+    let b = json.find("\"b\":").unwrap();
+    let c = json.find("\"c\":").unwrap();
+    assert!(a < b && b < c);
+}
+
+fn load_error(contents: &str, format: ConfigFormat) -> String {
+    match App::from_spec_str(contents, format).unwrap_err() {
+        AppError::ConfigurationError { message } => message,
+        err => panic!("Erro esperado: ConfigurationError, erro recebido: {:?}", err),
+    }
+}
+
+#[test]
+fn test_spec_round_trip() {
+    let app = test_app();
+    let json = app.to_spec_json();
+
+    let loaded = App::from_spec_str(&json, ConfigFormat::Json).unwrap();
+    assert_eq!(loaded.to_spec_json(), json);
+}
+
+#[test]
+fn test_from_toml_spec() {
+    let spec = r#"
+name = "deploy"
+version = "2.0.0"
+
+[flags.verbose]
+type = "bool"
+short = "v"
+global = true
+
+[commands.calc.commands.add]
+description = "Soma números"
+
+[commands.calc.commands.add.flags.numbers]
+type = "integer_list"
+required = true
+
+[[commands.calc.commands.add.positional_args]]
+name = "label"
+required = false
+"#;
+
+    let app = App::from_spec_str(spec, ConfigFormat::Toml).unwrap();
+    assert_eq!(app.name, "deploy");
+
+    // Listas consomem os argumentos seguintes, inclusive o posicional
+    assert!(matches!(
+        app.parse(vec!["calc", "add", "-v", "--numbers", "1", "x"]),
+        Err(AppError::InvalidFlagValue { ref value, .. }) if value == "x"
+    ));
+
+    let parsed = app.parse(vec!["calc", "add", "-v", "--numbers=1", "x"]).unwrap();
+    assert_eq!(parsed.get_flag("numbers").unwrap(), &FlagValue::IntegerList(vec![1]));
+    assert_eq!(parsed.get_flag("verbose").unwrap(), &FlagValue::Bool(true));
+    assert_eq!(parsed.get_arg(0).unwrap(), "x");
+}
+
+#[test]
+fn test_from_yaml_spec_with_defaults() {
+    let spec = "
+name: app
+commands:
+  hello:
+    show_help_on_empty: false
+    flags:
+      format:
+        type: string
+        possible_values: [json, text]
+        default: text
+";
+
+    let app = App::from_spec_str(spec, ConfigFormat::Yaml).unwrap();
+    let parsed = app.parse(vec!["hello"]).unwrap();
+    assert_eq!(parsed.get_flag("format").unwrap(), &FlagValue::String("text".to_string()));
+}
+
+#[test]
+fn test_spec_errors_cite_path() {
+    let message = load_error(
+        r#"{"name": "app", "commands": {"calc": {"flags": {"numbers": {"type": "lista"}}}}}"#,
+        ConfigFormat::Json,
+    );
+    assert!(message.starts_with("commands.calc.flags.numbers.type: tipo desconhecido"));
+
+    let message = load_error(
+        r#"{"name": "app", "commands": {"calc": {"flags": {"numbers": {"type": "integer_list", "default": "um"}}}}}"#,
+        ConfigFormat::Json,
+    );
+    assert!(message.starts_with("commands.calc.flags.numbers.default: esperado integer list"));
+
+    let message = load_error(r#"{"name": "app", "comands": {}}"#, ConfigFormat::Json);
+    assert_eq!(message, "comands: campo desconhecido");
+
+    let message = load_error(r#"{"version": "1.0.0"}"#, ConfigFormat::Json);
+    assert_eq!(message, "name: campo obrigatório");
+}
+
+#[test]
+fn test_spec_runs_validate() {
+    let message = load_error(
+        r#"{"name": "app", "commands": {"calc": {"flags": {"numbers": {"type": "integer", "required": true, "default": 1}}}}}"#,
+        ConfigFormat::Json,
+    );
+
+    assert_eq!(
+        message,
+        "commands.calc.flags.numbers: Flag 'numbers' não pode ser obrigatória e ter valor padrão"
+    );
+
+    let message = load_error(
+        r#"{"name": "app", "groups": [{"name": "formato", "kind": "exactly_one", "flags": ["json"]}]}"#,
+        ConfigFormat::Json,
+    );
+    assert_eq!(
+        message,
+        "groups[0]: Grupo 'formato' referencia a flag 'json', que não existe no comando 'app'"
+    );
+
+    let message = load_error(
+        r#"{"name": "app", "groups": [{"name": "formato", "kind": "one", "flags": []}]}"#,
+        ConfigFormat::Json,
+    );
+    assert!(message.starts_with("groups[0].kind: tipo de grupo desconhecido 'one'"));
+}
+
+#[test]
+fn test_from_spec_file() {
+    let temp = TempDir::new("spec");
+    let path = temp.path().join("app.yaml");
+    std::fs::write(&path, "name: app\nflags:\n  verbose:\n    type: boleano\n").unwrap();
+
+    let error = App::from_spec_file(&path).unwrap_err();
+    assert!(error.to_string().contains(&format!("{}: flags.verbose.type", path.display())));
+}
@@ -0,0 +1,63 @@
+/// Distância de edição (Levenshtein) entre duas strings, por caractere
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b_chars: Vec<char> = b.chars().collect();
+    let mut previous: Vec<usize> = (0..=b_chars.len()).collect();
+
+    for (i, a_char) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b_chars.len() + 1];
+
+        for (j, b_char) in b_chars.iter().enumerate() {
+            let substitution = previous[j] + usize::from(a_char != *b_char);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+
+        previous = current;
+    }
+
+    previous[b_chars.len()]
+}
+
+/// Retorna os candidatos parecidos com `input`, do mais para o menos parecido.
+/// Um candidato é considerado parecido quando a distância de edição não passa
+/// de um terço do tamanho da maior das duas strings
+pub(crate) fn did_you_mean<'a, I>(input: &str, candidates: I) -> Vec<String>
+where
+    I: IntoIterator<Item = &'a str>,
+{
+    let mut matches: Vec<(usize, &str)> = candidates
+        .into_iter()
+        .filter(|candidate| *candidate != input)
+        .map(|candidate| (edit_distance(input, candidate), candidate))
+        .filter(|(distance, candidate)| {
+            let longest = input.chars().count().max(candidate.chars().count());
+            distance * 3 <= longest
+        })
+        .collect();
+
+    matches.sort();
+    matches.dedup();
+    matches.into_iter().map(|(_, candidate)| candidate.to_string()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("", ""), 0);
+        assert_eq!(edit_distance("verbose", "verbose"), 0);
+        assert_eq!(edit_distance("verbos", "verbose"), 1);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("ação", "acao"), 2);
+    }
+
+    #[test]
+    fn test_did_you_mean() {
+        let candidates = ["verbose", "version", "name"];
+
+        assert_eq!(did_you_mean("verbos", candidates), vec!["verbose"]);
+        assert_eq!(did_you_mean("versio", candidates), vec!["version"]);
+        assert!(did_you_mean("xyz", candidates).is_empty());
+    }
+}
@@ -0,0 +1,24 @@
+//! Utilitários compartilhados pelos testes do crate
+
+use std::path::{Path, PathBuf};
+
+/// Diretório temporário removido ao sair de escopo, mesmo se o teste falhar
+pub(crate) struct TempDir(PathBuf);
+
+impl TempDir {
+    pub(crate) fn new(name: &str) -> Self {
+        let dir = std::env::temp_dir().join(format!("cliparser-{}-{}", name, std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        Self(dir)
+    }
+
+    pub(crate) fn path(&self) -> &Path {
+        &self.0
+    }
+}
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.0);
+    }
+}
@@ -1,16 +1,45 @@
 
 use owo_colors::OwoColorize;
-use crate::{AppError, Command};
+use crate::{AppError, Command, Flag, ParsedArgs};
 
 pub struct ColoredUI;
 
 impl ColoredUI {
-    pub fn show_help(app_name: &str, version: &str, description: &str, command: &Command) {
-        println!("{}", Self::format_help(app_name, version, description, command));
+    pub fn show_help(
+        app_name: &str,
+        version: &str,
+        description: &str,
+        command_path: &[&str],
+        command: &Command,
+        global_flags: &[&Flag],
+    ) {
+        println!(
+            "{}",
+            Self::format_help(app_name, version, description, command_path, command, global_flags)
+        );
     }
 
     pub fn show_error(error: &AppError) {
         eprintln!("{} {}", "[ERROR]".bold().red().on_black(), error);
+
+        if let Some(hint) = Self::format_suggestions(error.suggestions()) {
+            eprintln!("        {}", hint.yellow());
+        }
+    }
+
+    fn format_suggestions(suggestions: &[String]) -> Option<String> {
+        let (last, rest) = suggestions.split_last()?;
+
+        if rest.is_empty() {
+            Some(format!("você quis dizer {}?", last))
+        } else {
+            Some(format!("você quis dizer {} ou {}?", rest.join(", "), last))
+        }
+    }
+
+    /// Exibe uma tabela com o valor e a origem de cada flag, por nível de comando
+    pub fn show_value_sources(app_name: &str, parsed: &ParsedArgs) {
+        println!("{}", Self::format_value_sources(app_name, parsed));
     }
 
     pub fn show_success(message: &str) {
@@ -25,7 +54,14 @@ impl ColoredUI {
         println!("{}, {}", "[INFO]".bold().blue().on_black(), message);
     }
 
-    fn format_help(app_name: &str, version: &str, description: &str, command: &Command) -> String {
+    fn format_help(
+        app_name: &str,
+        version: &str,
+        description: &str,
+        command_path: &[&str],
+        command: &Command,
+        global_flags: &[&Flag],
+    ) -> String {
         let mut help = String::new();
 
         help.push_str(&format!("{} v{}", app_name, version).cyan().to_string());
@@ -39,9 +75,28 @@ impl ColoredUI {
         // Uso
         help.push_str(&"USO".bold().yellow().to_string());
 
-        let usage = Self::format_usage(app_name, command);
+        let usage = Self::format_usage(app_name, command_path, command);
         help.push_str(&format!("\n    {}\n\n", usage));
 
+        // Subcomandos
+        if !command.get_visible_subcommands().is_empty() {
+            help.push_str(&format!("{}\n", "SUBCOMANDOS:".yellow().bold()));
+
+            let subcommands = command.get_visible_subcommands();
+            let width = subcommands.iter().map(|sub| sub.name.chars().count()).max().unwrap_or(0);
+
+            for subcommand in subcommands {
+                let padding = " ".repeat(width - subcommand.name.chars().count());
+                help.push_str(&format!(
+                    "    {}{}    {}\n",
+                    subcommand.name.green(),
+                    padding,
+                    subcommand.description
+                ));
+            }
+            help.push('\n');
+        }
+
         // Argumentos posicionais
         if command.has_positional_args() {
             help.push_str(&"ARGUMENTOS".yellow().to_string());
@@ -49,7 +104,7 @@ impl ColoredUI {
 
             for arg in &command.positional_args {
                 let req_marker = if arg.required { "" } else { " (opcional)" };
-                help.push_str(&format!("    {}{}\n{}", arg.name.green(), req_marker, arg.description));
+                help.push_str(&format!("    {}{}\n        {}\n", arg.name.green(), req_marker, arg.description));
             }
             help.push('\n');
         }
@@ -60,28 +115,50 @@ impl ColoredUI {
 
             let flags = command.get_flags_sorted();
             for flag in flags {
-                let short_part = if let Some(short) = flag.short {
-                    format!("-{} ", short)
-                }else {
-                    "    ".to_string()
-                };
+                help.push_str(&Self::format_flag(flag));
+            }
+
+            help.push('\n');
+        }
 
-                let type_hint = match flag.flag_type {
-                    crate::flag::FlagType::Bool => "".to_string(),
-                    _ => format!("<{}>", flag.flag_type.description())
-                };
+        // Grupos de flags
+        if !command.groups.is_empty() {
+            help.push_str(&format!("{}\n", "GRUPOS:".yellow().bold()));
 
-                let required_maker = if flag.required { "" } else { " (opcional)" };
+            let width = command.groups.iter().map(|group| group.name.chars().count()).max().unwrap_or(0);
 
-                help.push_str(&format!("    {} --{}{}\n        {}{}",
-                                       short_part, flag.name, type_hint, flag.description, required_maker));
+            for group in &command.groups {
+                help.push_str(&format!(
+                    "    {}{}    {} {}\n",
+                    group.name.green(),
+                    " ".repeat(width - group.name.chars().count()),
+                    group.kind.description(),
+                    Self::format_flag_names(&group.flags)
+                ));
+            }
 
-                if let Some(ref possible) = flag.possible_values {
-                    help.push_str(&format!("        Valores possíveis: {}\n", possible.join(", ")));
-                }
+            help.push('\n');
+        }
+
+        // Flags globais herdadas
+        if !global_flags.is_empty() {
+            help.push_str(&format!("{}\n", "OPÇÕES GLOBAIS:".yellow().bold()));
 
-                if let Some(ref default) = flag.default_value {
-                    help.push_str(&format!("        Padrão: {:?}\n", default));
+            for flag in global_flags {
+                help.push_str(&Self::format_flag(flag));
+            }
+
+            help.push('\n');
+        }
+
+        // Exemplos
+        if !command.examples.is_empty() {
+            help.push_str(&format!("{}\n", "EXEMPLOS:".yellow().bold()));
+
+            for example in &command.examples {
+                help.push_str(&format!("    {}\n", example.command.green()));
+                if !example.description.is_empty() {
+                    help.push_str(&format!("        {}\n", example.description));
                 }
             }
 
@@ -91,14 +168,149 @@ impl ColoredUI {
         help
     }
 
-    fn format_usage(app_name: &str, command: &Command) -> String {
+    fn format_flag(flag: &Flag) -> String {
+        let mut line = String::new();
+
+        let short_part = if let Some(short) = flag.short {
+            format!("-{} ", short)
+        }else {
+            "    ".to_string()
+        };
+
+        let type_hint = if !flag.flag_type.takes_value() {
+            "".to_string()
+        } else if flag.default_missing_value.is_some() {
+            format!("[=<{}>]", flag.flag_type.description())
+        } else {
+            format!(" <{}>", flag.flag_type.description())
+        };
+
+        let required_maker = if flag.required || !flag.required_unless.is_empty() {
+            ""
+        } else {
+            " (opcional)"
+        };
+
+        line.push_str(&format!("    {} --{}{}\n        {}{}\n",
+                               short_part, flag.display_name(), type_hint, flag.description, required_maker));
+
+        if let Some(ref possible) = flag.possible_values {
+            line.push_str(&format!("        Valores possíveis: {}\n", possible.join(", ")));
+        }
+
+        if let Some(range) = flag.num_values {
+            line.push_str(&format!("        Valores por ocorrência: {}\n", range));
+        }
+
+        if let Some(ref env) = flag.env {
+            line.push_str(&format!("        Variável de ambiente: {}\n", env));
+        }
+
+        if let Some(ref default) = flag.default_value {
+            line.push_str(&format!("        Padrão: {:?}\n", default));
+        }
+
+        if let Some(ref default_missing) = flag.default_missing_value {
+            line.push_str(&format!("        Sem valor: {}\n", default_missing));
+        }
+
+        if let crate::flag::FlagType::Count = flag.flag_type {
+            match flag.decrements {
+                Some(ref target) => line.push_str(&format!(
+                    "        Pode ser repetida; cada ocorrência diminui --{}\n",
+                    target
+                )),
+                None => line.push_str("        Pode ser repetida; cada ocorrência soma um\n"),
+            }
+        }
+
+        if !flag.required_unless.is_empty() {
+            line.push_str(&format!(
+                "        Obrigatória, a menos que seja informada: {}\n",
+                Self::format_flag_names(&flag.required_unless)
+            ));
+        }
+
+        if !flag.requires.is_empty() {
+            line.push_str(&format!("        Requer: {}\n", Self::format_flag_names(&flag.requires)));
+        }
+
+        if !flag.conflicts_with.is_empty() {
+            line.push_str(&format!(
+                "        Conflita com: {}\n",
+                Self::format_flag_names(&flag.conflicts_with)
+            ));
+        }
+
+        line
+    }
+
+    /// `["json", "table"]` vira `--json, --table`
+    fn format_flag_names(names: &[String]) -> String {
+        names
+            .iter()
+            .map(|name| format!("--{}", name))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+
+    fn format_value_sources(app_name: &str, parsed: &ParsedArgs) -> String {
+        let mut output = format!("{}\n", "ORIGEM DOS VALORES:".yellow().bold());
+        let mut label = app_name.to_string();
+        let mut level = Some(parsed);
+
+        while let Some(current) = level {
+            if current.command != app_name {
+                label.push_str(&format!(" {}", current.command));
+            }
+
+            let mut names: Vec<&String> = current.flags.keys().collect();
+            names.sort();
+
+            if !names.is_empty() {
+                output.push_str(&format!("    {}\n", label.cyan()));
+
+                let rows: Vec<(String, String, String)> = names
+                    .into_iter()
+                    .map(|name| {
+                        let source = current
+                            .sources
+                            .get(name)
+                            .map(|source| source.to_string())
+                            .unwrap_or_default();
+                        (format!("--{}", name), current.flags[name].to_string(), source)
+                    })
+                    .collect();
+
+                let name_width = rows.iter().map(|row| row.0.chars().count()).max().unwrap_or(0);
+                let value_width = rows.iter().map(|row| row.1.chars().count()).max().unwrap_or(0);
+
+                for (name, value, source) in rows {
+                    output.push_str(&format!(
+                        "        {}{}    {}{}    {}\n",
+                        name.green(),
+                        " ".repeat(name_width - name.chars().count()),
+                        value,
+                        " ".repeat(value_width - value.chars().count()),
+                        source
+                    ));
+                }
+            }
+
+            level = current.subcommand_matches();
+        }
+
+        output
+    }
+
+    pub(crate) fn format_usage(app_name: &str, command_path: &[&str], command: &Command) -> String {
         let mut usage = app_name.to_string();
 
-        if command.name != app_name {
-            usage.push_str(&format!(" {}", command.name));
+        for name in command_path {
+            usage.push_str(&format!(" {}", name));
         }
 
-        if command.has_subcommands() {
+        if !command.get_visible_subcommands().is_empty() {
             usage.push_str(" <SUBCOMANDO>");
         }
 
@@ -121,8 +333,10 @@ impl ColoredUI {
 
 #[cfg(test)]
 mod tests {
-    use crate::Command;
+    use crate::command::{ArgGroup, GroupKind};
+    use crate::flag::FlagValue;
     use crate::ui::ColoredUI;
+    use crate::{Command, Flag, FlagType, ParsedArgs, ValueSource};
 
     #[test]
     fn test_format_help_basic() {
@@ -131,11 +345,136 @@ mod tests {
             "myapp",
             "1.0.0",
             "Uma descrição de testes",
-            &command
+            &[],
+            &command,
+            &[]
         );
 
         assert!(help.contains("myapp v1.0.0"));
         assert!(help.contains("Uma descrição de testes"));
         assert!(help.contains("USO"));
     }
+
+    #[test]
+    fn test_format_help_shows_env_var() {
+        let command = Command::new("app").add_flag(Flag::new("token", FlagType::String).env("APP_TOKEN"));
+        let help = ColoredUI::format_help("app", "1.0.0", "", &[], &command, &[]);
+
+        assert!(help.contains("Variável de ambiente: APP_TOKEN"));
+    }
+
+    #[test]
+    fn test_format_value_sources() {
+        let mut parsed = ParsedArgs::new("app".to_string());
+        parsed.flags.insert("timeout".to_string(), FlagValue::Integer(30));
+        parsed.sources.insert("timeout".to_string(), ValueSource::Default);
+
+        let mut sub = ParsedArgs::new("hello".to_string());
+        sub.flags.insert("name".to_string(), FlagValue::String("Rafael".to_string()));
+        sub.sources.insert("name".to_string(), ValueSource::Environment("APP_NAME".to_string()));
+        parsed.subcommand_matches = Some(Box::new(sub));
+
+        let output = ColoredUI::format_value_sources("app", &parsed);
+
+        assert!(output.contains("ORIGEM DOS VALORES"));
+        assert!(output.contains("app hello"));
+        assert!(output.contains("valor padrão"));
+        assert!(output.contains("Rafael"));
+        assert!(output.contains("variável de ambiente APP_NAME"));
+    }
+
+    #[test]
+    fn test_format_suggestions() {
+        assert_eq!(ColoredUI::format_suggestions(&[]), None);
+        assert_eq!(
+            ColoredUI::format_suggestions(&["--verbose".to_string()]),
+            Some("você quis dizer --verbose?".to_string())
+        );
+        assert_eq!(
+            ColoredUI::format_suggestions(&["a".to_string(), "b".to_string(), "c".to_string()]),
+            Some("você quis dizer a, b ou c?".to_string())
+        );
+    }
+
+    #[test]
+    fn test_format_usage_nested_path() {
+        let command = Command::new("add").add_flag(Flag::new("numbers", FlagType::IntegerList));
+        let usage = ColoredUI::format_usage("app", &["calc", "add"], &command);
+
+        assert_eq!(usage, "app calc add [OPÇÕES]");
+    }
+
+    #[test]
+    fn test_format_help_lists_subcommands() {
+        let command = Command::new("calc")
+            .add_subcommand(Command::new("add").description("Soma números"))
+            .add_subcommand(Command::new("multiply").description("Multiplica números"));
+        let help = ColoredUI::format_help("app", "1.0.0", "", &["calc"], &command, &[]);
+
+        assert!(help.contains("app calc <SUBCOMANDO>"));
+        assert!(help.contains("SUBCOMANDOS"));
+        assert!(help.contains("Soma números"));
+        assert!(help.contains("Multiplica números"));
+    }
+
+    #[test]
+    fn test_format_help_examples() {
+        let command = Command::new("add").example("app calc add --numbers 1 2", "Soma 1 e 2");
+        let help = ColoredUI::format_help("app", "1.0.0", "", &["calc", "add"], &command, &[]);
+
+        assert!(help.contains("EXEMPLOS"));
+        assert!(help.contains("app calc add --numbers 1 2"));
+        assert!(help.contains("Soma 1 e 2"));
+    }
+
+    #[test]
+    fn test_format_help_relationships_and_groups() {
+        let command = Command::new("export")
+            .add_flag(Flag::new("json", FlagType::Bool).conflicts_with("table"))
+            .add_flag(Flag::new("table", FlagType::Bool))
+            .add_flag(Flag::new("password", FlagType::String).requires("user"))
+            .add_flag(Flag::new("user", FlagType::String).required_unless("token"))
+            .add_flag(Flag::new("token", FlagType::String))
+            .add_group(ArgGroup::new("formato", GroupKind::ExactlyOne).flag("json").flag("table"));
+        let help = ColoredUI::format_help("app", "1.0.0", "", &["export"], &command, &[]);
+
+        assert!(help.contains("Conflita com: --table"));
+        assert!(help.contains("Requer: --user"));
+        assert!(help.contains("Obrigatória, a menos que seja informada: --token"));
+        assert!(help.contains("GRUPOS"));
+        assert!(help.contains("exatamente uma de --json, --table"));
+    }
+
+    #[test]
+    fn test_format_help_negatable_flag() {
+        let command = Command::new("build")
+            .add_flag(Flag::new("color", FlagType::Bool).negatable(true))
+            .add_flag(Flag::new("cache", FlagType::Bool));
+        let help = ColoredUI::format_help("app", "1.0.0", "", &["build"], &command, &[]);
+
+        assert!(help.contains("--[no-]color"));
+        assert!(!help.contains("--[no-]cache"));
+    }
+
+    #[test]
+    fn test_format_help_optional_value() {
+        let command = Command::new("ls").add_flag(
+            Flag::new("color", FlagType::String).optional_value(FlagValue::String("auto".to_string())),
+        );
+        let help = ColoredUI::format_help("app", "1.0.0", "", &["ls"], &command, &[]);
+
+        assert!(help.contains("--color[=<string>]"));
+        assert!(help.contains("Sem valor: auto"));
+    }
+
+    #[test]
+    fn test_format_help_global_flags_section() {
+        let verbose = Flag::new("verbose", FlagType::Bool).global(true).description("Modo verboso");
+        let command = Command::new("hello").add_flag(Flag::new("name", FlagType::String));
+        let help = ColoredUI::format_help("app", "1.0.0", "", &["hello"], &command, &[&verbose]);
+
+        assert!(help.contains("OPÇÕES GLOBAIS"));
+        assert!(help.contains("--verbose"));
+        assert!(help.contains("Modo verboso"));
+    }
 }
\ No newline at end of file
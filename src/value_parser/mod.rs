@@ -0,0 +1,143 @@
+//! Conversão dos valores de flags para tipos definidos pelo usuário, lidos com
+//! `ParsedArgs::get` e `ParsedArgs::get_many`
+
+use crate::flag::FlagValue;
+use std::any::Any;
+use std::fmt;
+use std::marker::PhantomData;
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// Converte o texto de cada valor de uma flag em `Value`. Closures
+/// `Fn(&str) -> Result<T, E>` com `E: Display` implementam o trait
+pub trait ValueParser: Send + Sync + 'static {
+    type Value: Any + Clone + Send + Sync;
+
+    /// Converte `value`; o erro descreve o valor esperado
+    fn parse(&self, value: &str) -> Result<Self::Value, String>;
+}
+
+impl<F, T, E> ValueParser for F
+where
+    F: Fn(&str) -> Result<T, E> + Send + Sync + 'static,
+    T: Any + Clone + Send + Sync,
+    E: fmt::Display,
+{
+    type Value = T;
+
+    fn parse(&self, value: &str) -> Result<T, String> {
+        self(value).map_err(|error| error.to_string())
+    }
+}
+
+/// `ValueParser` de qualquer tipo que implemente `FromStr`, criado por `from_str`
+pub struct FromStrParser<T>(PhantomData<fn() -> T>);
+
+impl<T> ValueParser for FromStrParser<T>
+where
+    T: FromStr + Any + Clone + Send + Sync,
+    T::Err: fmt::Display,
+{
+    type Value = T;
+
+    fn parse(&self, value: &str) -> Result<T, String> {
+        value.parse().map_err(|error: T::Err| error.to_string())
+    }
+}
+
+/// Parser que usa `T::from_str`, ex: `Flag::new("addr", FlagType::String).value_parser(from_str::<IpAddr>())`
+pub fn from_str<T>() -> FromStrParser<T>
+where
+    T: FromStr + Any + Clone + Send + Sync,
+    T::Err: fmt::Display,
+{
+    FromStrParser(PhantomData)
+}
+
+/// Valor de tipo apagado guardado em `ParsedArgs`
+#[derive(Clone)]
+pub struct AnyValue {
+    value: Arc<dyn Any + Send + Sync>,
+    type_name: &'static str,
+}
+
+impl AnyValue {
+    pub fn new<T: Any + Send + Sync>(value: T) -> Self {
+        Self {
+            value: Arc::new(value),
+            type_name: std::any::type_name::<T>(),
+        }
+    }
+
+    pub fn downcast_ref<T: Any>(&self) -> Option<&T> {
+        self.value.downcast_ref()
+    }
+
+    /// Nome do tipo guardado, usado nas mensagens de erro
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+}
+
+impl fmt::Debug for AnyValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("AnyValue").field(&self.type_name).finish()
+    }
+}
+
+type ParseFn = dyn Fn(&str) -> Result<AnyValue, String> + Send + Sync;
+
+/// `ValueParser` de tipo apagado, guardado em `Flag::value_parser`
+#[derive(Clone)]
+pub struct AnyValueParser {
+    parse: Arc<ParseFn>,
+    type_name: &'static str,
+}
+
+impl AnyValueParser {
+    pub fn new<P: ValueParser>(parser: P) -> Self {
+        Self {
+            parse: Arc::new(move |value| parser.parse(value).map(AnyValue::new)),
+            type_name: std::any::type_name::<P::Value>(),
+        }
+    }
+
+    pub fn parse(&self, value: &str) -> Result<AnyValue, String> {
+        (self.parse)(value)
+    }
+
+    pub fn type_name(&self) -> &'static str {
+        self.type_name
+    }
+}
+
+impl fmt::Debug for AnyValueParser {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("AnyValueParser").field(&self.type_name).finish()
+    }
+}
+
+/// Texto de cada valor de `value`, entregue ao `ValueParser` da flag
+pub(crate) fn raw_values(value: &FlagValue) -> Vec<String> {
+    match value {
+        FlagValue::StringList(list) => list.clone(),
+        FlagValue::IntegerList(list) => list.iter().map(i64::to_string).collect(),
+        _ => vec![value.to_string()],
+    }
+}
+
+/// Valores de uma flag sem `ValueParser`, com o tipo Rust de cada `FlagType`:
+/// `bool`, `String`, `i64` ou `f64`
+pub(crate) fn builtin_values(value: &FlagValue) -> Vec<AnyValue> {
+    match value {
+        FlagValue::Bool(value) => vec![AnyValue::new(*value)],
+        FlagValue::String(value) => vec![AnyValue::new(value.clone())],
+        FlagValue::Integer(value) => vec![AnyValue::new(*value)],
+        FlagValue::Float(value) => vec![AnyValue::new(*value)],
+        FlagValue::StringList(list) => list.iter().cloned().map(AnyValue::new).collect(),
+        FlagValue::IntegerList(list) => list.iter().copied().map(AnyValue::new).collect(),
+    }
+}
+
+#[cfg(test)]
+mod tests;
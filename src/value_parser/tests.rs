@@ -0,0 +1,106 @@
+use super::*;
+use crate::env::EnvSource;
+use crate::parser::CLIParser;
+use crate::parser::tests::parse;
+use crate::{AppError, Command, Flag, FlagType};
+use std::collections::HashMap;
+use std::net::IpAddr;
+use std::time::Duration;
+
+fn parse_duration(value: &str) -> Result<Duration, String> {
+    let seconds = value
+        .strip_suffix('s')
+        .ok_or_else(|| "duração em segundos, ex: 30s".to_string())?;
+    seconds
+        .parse()
+        .map(Duration::from_secs)
+        .map_err(|_| "duração em segundos, ex: 30s".to_string())
+}
+
+fn test_command() -> Command {
+    Command::new("app")
+        .add_flag(Flag::new("timeout", FlagType::String).value_parser(parse_duration))
+        .add_flag(
+            Flag::new("hosts", FlagType::StringList)
+                .global(true)
+                .value_parser(from_str::<IpAddr>()),
+        )
+        .add_flag(Flag::new("retries", FlagType::Integer))
+        .add_flag(Flag::new("name", FlagType::String))
+        .add_subcommand(
+            Command::new("deploy")
+                .add_flag(Flag::new("port", FlagType::Integer).value_parser(from_str::<u16>())),
+        )
+}
+
+#[test]
+fn test_get_with_value_parser() {
+    let parsed = parse(&test_command(), &["--timeout", "30s", "--retries", "3"]).unwrap();
+
+    assert_eq!(parsed.get::<Duration>("timeout").unwrap(), Some(Duration::from_secs(30)));
+    assert_eq!(parsed.get::<i64>("retries").unwrap(), Some(3));
+    assert_eq!(parsed.get::<String>("name").unwrap(), None);
+}
+
+#[test]
+fn test_get_many_with_global_flag_in_subcommand() {
+    let parsed = parse(&test_command(), &["deploy", "--port", "8080", "--hosts", "10.0.0.1", "--hosts", "::1"]).unwrap();
+
+    let hosts = parsed.get_many::<IpAddr>("hosts").unwrap().unwrap();
+    assert_eq!(hosts, vec!["10.0.0.1".parse::<IpAddr>().unwrap(), "::1".parse().unwrap()]);
+    assert_eq!(parsed.get::<u16>("port").unwrap(), Some(8080));
+}
+
+#[test]
+fn test_type_mismatch_is_an_error() {
+    let parsed = parse(&test_command(), &["--timeout", "5s", "--retries", "3"]).unwrap();
+
+    assert_eq!(
+        parsed.get::<String>("timeout").unwrap_err(),
+        AppError::ValueTypeMismatch {
+            flag: "timeout".to_string(),
+            expected: "alloc::string::String".to_string(),
+            actual: "core::time::Duration".to_string(),
+        }
+    );
+    assert!(matches!(
+        parsed.get::<i32>("retries"),
+        Err(AppError::ValueTypeMismatch { .. })
+    ));
+}
+
+#[test]
+fn test_value_parser_error() {
+    let error = parse(&test_command(), &["deploy", "--port", "99999"]).unwrap_err();
+    assert!(matches!(
+        error,
+        AppError::InvalidFlagValue { ref flag, ref value, .. } if flag == "port" && value == "99999"
+    ));
+
+    let error = parse(&test_command(), &["--timeout", "30"]).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "Valor inválido para flag: --timeout: 30. Esperado: duração em segundos, ex: 30s"
+    );
+}
+
+#[test]
+fn test_value_parser_applies_to_env_and_default() {
+    let command = Command::new("app")
+        .show_help_on_empty(false)
+        .add_flag(
+            Flag::new("timeout", FlagType::String)
+                .env("APP_TIMEOUT")
+                .value_parser(parse_duration),
+        )
+        .add_flag(
+            Flag::new("interval", FlagType::String)
+                .default_value(crate::flag::FlagValue::String("10s".to_string()))
+                .value_parser(parse_duration),
+        );
+    let env = EnvSource::Map(HashMap::from([("APP_TIMEOUT".to_string(), "2s".to_string())]));
+
+    let parsed = CLIParser::parse_with_env(&command, Vec::new(), &env).unwrap();
+    assert_eq!(parsed.get::<Duration>("timeout").unwrap(), Some(Duration::from_secs(2)));
+    assert_eq!(parsed.get::<Duration>("interval").unwrap(), Some(Duration::from_secs(10)));
+}
@@ -1,6 +1,6 @@
 #[cfg(test)]
 mod integration_tests {
-    use cliparser::{App, Command, Flag, FlagType, flag::FlagValue};
+    use cliparser::{App, Command, Flag, FlagType, ValueSource, flag::FlagValue};
 
     fn create_test_app() -> App {
         App::new("test-app", "1.0.0")
@@ -90,6 +90,34 @@ mod integration_tests {
         )
     }
 
+    #[test]
+    fn test_global_flags_after_subcommand() {
+        let app = create_test_app();
+
+        let result = app.parse(vec!["hello", "--name", "Teste", "--verbose", "-c", "custom.toml"]);
+        assert!(result.is_ok(), "parse falhou com erro {:?}", result.unwrap_err());
+
+        let parsed = result.unwrap();
+        assert!(parsed.get_flag("verbose").unwrap().as_bool().unwrap());
+        assert_eq!(
+            parsed.get_flag("config").unwrap().as_string().unwrap(),
+            "custom.toml"
+        );
+    }
+
+    #[test]
+    fn test_value_source() {
+        let app = create_test_app();
+
+        let parsed = app.parse(vec!["hello", "--name", "Teste"]).unwrap();
+        assert_eq!(parsed.value_source("name"), Some(&ValueSource::CommandLine));
+        assert_eq!(parsed.value_source("config"), Some(&ValueSource::Default));
+        assert_eq!(parsed.value_source("times"), Some(&ValueSource::Default));
+
+        let parsed = app.parse(vec!["--config", "custom.toml", "hello", "--name", "Teste"]).unwrap();
+        assert_eq!(parsed.value_source("config"), Some(&ValueSource::CommandLine));
+    }
+
     #[test]
     fn test_simple_command() {
         let app = create_test_app();
@@ -189,7 +217,7 @@ mod integration_tests {
         assert!(result.is_err());
 
         match result.unwrap_err() {
-            cliparser::AppError::UnknownFlag { flag } => {
+            cliparser::AppError::UnknownFlag { flag, .. } => {
                 assert_eq!(flag, "unknown");
             }
             _ => panic!("Erro esperado: UnknownFlag"),
@@ -204,7 +232,7 @@ mod integration_tests {
         assert!(result.is_err(), "O parse não retornou um erro, retornou {:?}", result);
 
         match result.unwrap_err() {
-            cliparser::AppError::CommandNotFound { command } => {
+            cliparser::AppError::CommandNotFound { command, .. } => {
                 assert_eq!(command, "unknown-command");
             }
             err => panic!("Erro esperado: CommandNotFound, erro recebido: {:?}", err),
@@ -254,6 +282,7 @@ mod integration_tests {
                 flag,
                 value,
                 expected,
+                ..
             } => {
                 assert_eq!(flag, "choice");
                 assert_eq!(value, "invalid");
@@ -285,6 +314,7 @@ mod integration_tests {
                 flag,
                 value,
                 expected,
+                ..
             } => {
                 assert_eq!(flag, "number");
                 assert_eq!(value, "not-a-number");